@@ -0,0 +1,364 @@
+use crate::plans::{PlanMeta, Plans};
+use crate::{ApiErrorCode, AppError, ErrorResponse};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use optimizer_core::{CancelToken, OptimizationRequest, OptimizationResult, Optimizer, Progress};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Semaphore};
+use tracing::{error, info};
+
+/// Lifecycle of an optimization job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for a free worker
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    /// Cancelled by the client; `result` holds the best solution found until
+    /// then, if the job had started
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Job state as returned by `GET /api/jobs/{id}`
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub status: JobStatus,
+    /// Latest progress report (while running)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<Progress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<OptimizationResult>,
+    /// Why the job failed, with the same fields as an error response
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<ErrorResponse>,
+    #[serde(skip)]
+    cancel: CancelToken,
+    #[serde(skip)]
+    events: broadcast::Sender<JobEvent>,
+    /// When the job reached its final status
+    #[serde(skip)]
+    finished_at: Option<Instant>,
+}
+
+/// Update pushed to the event stream subscribers of a job
+#[derive(Debug, Clone)]
+enum JobEvent {
+    Progress(Progress),
+    /// The job reached a final status
+    Finished(Box<Job>),
+}
+
+/// Number of events a slow subscriber may fall behind before it skips some
+const EVENT_BUFFER: usize = 64;
+
+/// How long a finished job is kept when nobody fetches its result
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Most finished jobs kept at once; beyond that the oldest are dropped
+const MAX_FINISHED_JOBS: usize = 1000;
+
+/// In-memory job table plus the worker pool that runs the jobs.
+/// At most `max_concurrent` optimizations run at once, each on tokio's
+/// blocking thread pool; further jobs wait in `Queued`. `POST /api/optimize`
+/// shares the pool. Completed results are stored as plans under the job id.
+///
+/// A finished job is kept, so clients can fetch it again, until it is deleted,
+/// `FINISHED_JOB_TTL` passes or more than `MAX_FINISHED_JOBS` are waiting.
+#[derive(Clone)]
+pub struct Jobs {
+    jobs: Arc<Mutex<HashMap<String, Job>>>,
+    workers: Arc<Semaphore>,
+    plans: Plans,
+    finished_ttl: Duration,
+    max_finished: usize,
+}
+
+impl Jobs {
+    pub fn new(max_concurrent: usize, plans: Plans) -> Self {
+        Self {
+            jobs: Arc::default(),
+            workers: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            plans,
+            finished_ttl: FINISHED_JOB_TTL,
+            max_finished: MAX_FINISHED_JOBS,
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<Job> {
+        let mut table = self.jobs.lock().unwrap();
+        self.evict_expired(&mut table);
+        table.get(id).cloned()
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            apply(job);
+        }
+    }
+
+    /// Drops finished jobs past their time to live, then the oldest finished
+    /// jobs beyond the cap.
+    fn evict_expired(&self, table: &mut HashMap<String, Job>) {
+        let now = Instant::now();
+        table.retain(|_, job| {
+            job.finished_at
+                .is_none_or(|at| now.duration_since(at) < self.finished_ttl)
+        });
+
+        let mut finished: Vec<(Instant, String)> = table
+            .values()
+            .filter_map(|job| Some((job.finished_at?, job.id.clone())))
+            .collect();
+        if finished.len() > self.max_finished {
+            finished.sort();
+            for (_, id) in &finished[..finished.len() - self.max_finished] {
+                table.remove(id);
+            }
+        }
+    }
+
+    /// Runs an optimization on the worker pool and waits for its result.
+    pub async fn run(&self, optimizer: Optimizer) -> Result<OptimizationResult, AppError> {
+        let Ok(_permit) = self.workers.clone().acquire_owned().await else {
+            return Err(AppError::internal(
+                "Optimization workers are shut down".into(),
+            ));
+        };
+
+        tokio::task::spawn_blocking(move || optimizer.optimize())
+            .await
+            .map_err(|err| AppError::internal(format!("Optimization worker failed: {}", err)))?
+            .map_err(AppError::from)
+    }
+
+    /// Registers a job and runs it in the background once a worker is free.
+    fn submit(&self, request: OptimizationRequest, meta: PlanMeta) -> Result<Job, AppError> {
+        let optimizer = Optimizer::new(request.clone())?;
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            status: JobStatus::Queued,
+            progress: None,
+            result: None,
+            failure: None,
+            cancel: CancelToken::new(),
+            events: broadcast::channel(EVENT_BUFFER).0,
+            finished_at: None,
+        };
+        {
+            let mut table = self.jobs.lock().unwrap();
+            self.evict_expired(&mut table);
+            table.insert(job.id.clone(), job.clone());
+        }
+
+        let jobs = self.clone();
+        let id = job.id.clone();
+        let cancel = job.cancel.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = jobs.workers.clone().acquire_owned().await else {
+                return;
+            };
+
+            // Cancelled while queued: never start
+            if cancel.is_cancelled() {
+                return;
+            }
+            jobs.update(&id, |job| job.status = JobStatus::Running);
+
+            let worker = jobs.clone();
+            let worker_id = id.clone();
+            let outcome = tokio::task::spawn_blocking(move || {
+                optimizer.optimize_with_progress(
+                    |progress| {
+                        worker.update(&worker_id, |job| {
+                            job.progress = Some(progress.clone());
+                            let _ = job.events.send(JobEvent::Progress(progress.clone()));
+                        })
+                    },
+                    &cancel,
+                )
+            })
+            .await;
+
+            if let Ok(Ok(result)) = &outcome {
+                if !result.cancelled {
+                    if let Err(err) = jobs.plans.save(&id, &meta, &request, result).await {
+                        error!("Failed to store plan {}: {}", id, err);
+                    }
+                }
+            }
+
+            jobs.update(&id, |job| {
+                match outcome {
+                    Ok(Ok(result)) => {
+                        job.status = if result.cancelled {
+                            JobStatus::Cancelled
+                        } else {
+                            JobStatus::Completed
+                        };
+                        job.result = Some(result);
+                    }
+                    Ok(Err(err)) => {
+                        job.status = JobStatus::Failed;
+                        job.failure = Some(ErrorResponse::from(&err));
+                    }
+                    Err(err) => {
+                        job.status = JobStatus::Failed;
+                        job.failure = Some(ErrorResponse::new(
+                            ApiErrorCode::Internal,
+                            format!("Optimization worker failed: {}", err),
+                        ));
+                    }
+                }
+                job.finished_at = Some(Instant::now());
+                let _ = job.events.send(JobEvent::Finished(Box::new(job.clone())));
+            });
+            info!("Job {} finished", id);
+        });
+
+        Ok(job)
+    }
+}
+
+fn job_not_found(id: &str) -> AppError {
+    AppError::not_found(format!("Job '{}' not found", id))
+}
+
+/// Validates the request, queues it and returns the new job id
+pub async fn create_job(
+    State(jobs): State<Jobs>,
+    Query(meta): Query<PlanMeta>,
+    Json(request): Json<OptimizationRequest>,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    info!(
+        "Queueing optimization job with {} items and {} panel types",
+        request.items.len(),
+        request.panel_types.len()
+    );
+
+    let job = jobs.submit(request, meta)?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Returns status, latest progress and (once finished) the result of a job.
+/// A completed result also stays available as the plan with the job id.
+pub async fn get_job(
+    State(jobs): State<Jobs>,
+    Path(id): Path<String>,
+) -> Result<Json<Job>, AppError> {
+    jobs.get(&id).map(Json).ok_or_else(|| job_not_found(&id))
+}
+
+/// Cancels a queued or running job. Finished jobs are removed instead.
+pub async fn delete_job(
+    State(jobs): State<Jobs>,
+    Path(id): Path<String>,
+) -> Result<Json<Job>, AppError> {
+    let mut table = jobs.jobs.lock().unwrap();
+    let Some(job) = table.get_mut(&id) else {
+        return Err(job_not_found(&id));
+    };
+
+    if job.status.is_finished() {
+        let job = table.remove(&id).unwrap();
+        return Ok(Json(job));
+    }
+
+    job.cancel.cancel();
+    if job.status == JobStatus::Queued {
+        job.status = JobStatus::Cancelled;
+        job.finished_at = Some(Instant::now());
+        let _ = job.events.send(JobEvent::Finished(Box::new(job.clone())));
+    }
+    info!("Job {} cancelled", id);
+
+    Ok(Json(job.clone()))
+}
+
+/// Streams a job as server-sent events: `progress` events while it runs
+/// (starting with the latest report), then one final `result` event with the
+/// optimization result, or `error` if the job failed or was cancelled before
+/// it started. Finished jobs send the final event right away.
+pub async fn job_events(
+    State(jobs): State<Jobs>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    // Snapshot and subscribe under the lock so no event is missed or repeated
+    let (first, live) = {
+        let mut table = jobs.jobs.lock().unwrap();
+        jobs.evict_expired(&mut table);
+        let Some(job) = table.get(&id) else {
+            return Err(job_not_found(&id));
+        };
+
+        if job.status.is_finished() {
+            (vec![final_event(job)], None)
+        } else {
+            let latest = job.progress.iter().map(progress_event).collect();
+            (latest, Some(job.events.subscribe()))
+        }
+    };
+
+    let live = stream::unfold(live, |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(JobEvent::Progress(progress)) => {
+                    return Some((progress_event(&progress), Some(receiver)))
+                }
+                Ok(JobEvent::Finished(job)) => return Some((final_event(&job), None)),
+                // Skipped reports are superseded by later ones
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::iter(first).chain(live).map(Ok);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn progress_event(progress: &Progress) -> Event {
+    Event::default()
+        .event("progress")
+        .json_data(progress)
+        .unwrap()
+}
+
+fn final_event(job: &Job) -> Event {
+    match (&job.result, &job.failure) {
+        (Some(result), _) => Event::default().event("result").json_data(result),
+        (None, Some(failure)) => Event::default().event("error").json_data(failure),
+        (None, None) => Event::default()
+            .event("error")
+            .json_data(ErrorResponse::new(
+                ApiErrorCode::Cancelled,
+                "Job cancelled before it started".to_string(),
+            )),
+    }
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests;
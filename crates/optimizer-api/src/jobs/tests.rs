@@ -0,0 +1,211 @@
+use super::*;
+use axum::response::IntoResponse;
+use optimizer_core::{Item, PanelType, SearchOptions};
+
+fn request(search_ms: Option<u64>) -> OptimizationRequest {
+    OptimizationRequest {
+        cut_width: 5.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "shelf".into(),
+            width: 600.0,
+            height: 300.0,
+            quantity: 4,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        search: search_ms.map(|ms| SearchOptions {
+            time_limit_ms: Some(ms),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn jobs(max_concurrent: usize) -> Jobs {
+    Jobs::new(max_concurrent, Plans::open(":memory:").unwrap())
+}
+
+fn status(jobs: &Jobs, id: &str) -> Option<JobStatus> {
+    jobs.jobs.lock().unwrap().get(id).map(|job| job.status)
+}
+
+/// Waits until the job reaches the given status
+async fn wait_for(jobs: &Jobs, id: &str, expected: JobStatus) {
+    for _ in 0..500 {
+        if status(jobs, id) == Some(expected) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("job {} never became {:?}", id, expected);
+}
+
+#[tokio::test]
+async fn test_job_completes_and_stays_until_deleted() {
+    let jobs = jobs(1);
+    let job = jobs.submit(request(None), PlanMeta::default()).unwrap();
+    wait_for(&jobs, &job.id, JobStatus::Completed).await;
+
+    let Json(fetched) = get_job(State(jobs.clone()), Path(job.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(fetched.status, JobStatus::Completed);
+    assert_eq!(fetched.result.unwrap().summary.total_panels, 1);
+
+    // Fetching again returns the same job until it is deleted
+    let Json(again) = get_job(State(jobs.clone()), Path(job.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(again.status, JobStatus::Completed);
+    let Json(removed) = delete_job(State(jobs.clone()), Path(job.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(removed.status, JobStatus::Completed);
+    assert!(get_job(State(jobs.clone()), Path(job.id.clone()))
+        .await
+        .is_err());
+
+    // The result was stored as a plan
+    assert!(
+        crate::plans::get_plan(State(jobs.plans.clone()), Path(job.id))
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_jobs_queue_behind_the_worker_limit_and_cancel() {
+    let jobs = jobs(1);
+    let running = jobs
+        .submit(request(Some(60_000)), PlanMeta::default())
+        .unwrap();
+    wait_for(&jobs, &running.id, JobStatus::Running).await;
+
+    let queued = jobs.submit(request(None), PlanMeta::default()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(status(&jobs, &queued.id), Some(JobStatus::Queued));
+
+    // A queued job is cancelled right away, without a result
+    let Json(cancelled) = delete_job(State(jobs.clone()), Path(queued.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status, JobStatus::Cancelled);
+    assert!(cancelled.result.is_none());
+
+    // A running job stops with the best result found so far
+    let Json(stopping) = delete_job(State(jobs.clone()), Path(running.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(stopping.status, JobStatus::Running);
+    wait_for(&jobs, &running.id, JobStatus::Cancelled).await;
+    let Json(stopped) = get_job(State(jobs.clone()), Path(running.id.clone()))
+        .await
+        .unwrap();
+    assert!(stopped.result.unwrap().cancelled);
+
+    // Deleting a finished job removes it
+    let Json(removed) = delete_job(State(jobs.clone()), Path(queued.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(removed.status, JobStatus::Cancelled);
+    assert_eq!(status(&jobs, &queued.id), None);
+}
+
+/// Collects the whole event stream of a job as (event, data) pairs
+async fn stream_events(jobs: &Jobs, id: &str) -> Vec<(String, serde_json::Value)> {
+    let sse = job_events(State(jobs.clone()), Path(id.to_string()))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(sse.into_response().into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec())
+        .unwrap()
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let field = |name: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let data = serde_json::from_str(&field("data: ")).unwrap();
+            (field("event: "), data)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_job_events_send_progress_before_the_result() {
+    let jobs = jobs(1);
+    let job = jobs.submit(request(None), PlanMeta::default()).unwrap();
+
+    let events = stream_events(&jobs, &job.id).await;
+    let (last, progress) = events.split_last().unwrap();
+    assert_eq!(last.0, "result");
+    assert_eq!(last.1["summary"]["total_panels"], 1);
+
+    assert!(!progress.is_empty());
+    assert!(progress.iter().all(|(event, data)| event == "progress"
+        && serde_json::from_value::<Progress>(data.clone()).is_ok()));
+    let improved = progress
+        .iter()
+        .map(|(_, data)| data)
+        .find(|data| data.get("layouts").is_some())
+        .expect("a progress event with layouts");
+    assert!(improved["phase"]["kind"].is_string());
+    assert_eq!(improved["best_panels"], 1);
+    assert!(improved["best_waste_percentage"].is_number());
+    assert_eq!(
+        improved["layouts"][0]["placements"]
+            .as_array()
+            .unwrap()
+            .len(),
+        4
+    );
+
+    // The job stays, and a later subscriber gets the result right away
+    assert_eq!(status(&jobs, &job.id), Some(JobStatus::Completed));
+    let again = stream_events(&jobs, &job.id).await;
+    assert_eq!(again.len(), 1);
+    assert_eq!(again[0], *last);
+}
+
+#[tokio::test]
+async fn test_finished_jobs_are_evicted_after_ttl_and_beyond_cap() {
+    let mut jobs = jobs(2);
+    jobs.max_finished = 1;
+    let first = jobs.submit(request(None), PlanMeta::default()).unwrap();
+    wait_for(&jobs, &first.id, JobStatus::Completed).await;
+    let second = jobs.submit(request(None), PlanMeta::default()).unwrap();
+    wait_for(&jobs, &second.id, JobStatus::Completed).await;
+
+    // Submitting evicts the oldest finished job beyond the cap
+    let third = jobs.submit(request(None), PlanMeta::default()).unwrap();
+    assert_eq!(status(&jobs, &first.id), None);
+    assert_eq!(status(&jobs, &second.id), Some(JobStatus::Completed));
+    wait_for(&jobs, &third.id, JobStatus::Completed).await;
+
+    // Expired jobs are gone on the next access
+    jobs.finished_ttl = Duration::ZERO;
+    jobs.submit(request(None), PlanMeta::default()).unwrap();
+    assert_eq!(status(&jobs, &second.id), None);
+    assert_eq!(status(&jobs, &third.id), None);
+}
+
+#[tokio::test]
+async fn test_run_uses_the_worker_pool() {
+    let jobs = jobs(1);
+    let optimizer = Optimizer::new(request(None)).unwrap();
+    let result = jobs.run(optimizer).await.unwrap();
+    assert_eq!(result.summary.total_panels, 1);
+    assert_eq!(jobs.workers.available_permits(), 1);
+}
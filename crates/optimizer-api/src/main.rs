@@ -1,14 +1,26 @@
 use axum::{
+    extract::{multipart::MultipartError, FromRef, Multipart, Query, State},
     http::StatusCode,
     response::{Html, IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-use optimizer_core::{OptimizationRequest, OptimizationResult, Optimizer, OptimizerError};
+use jobs::Jobs;
+use optimizer_core::{
+    import_cut_list, render_dxf, render_pdf, render_svg, result_to_csv, result_to_xlsx, validate,
+    verify, CutListOptions, DxfOptions, ErrorCode, ExportTable, FieldError, Item, ItemSize,
+    LayoutEdit, OptimizationRequest, OptimizationResult, Optimizer, OptimizerError, PanelSize,
+    PdfOptions, SvgOptions, ValidationReport, Violation,
+};
+use plans::{PlanMeta, Plans};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tower_http::cors::CorsLayer;
 use tracing::{error, info};
 
+mod jobs;
+mod plans;
+
 const OPENAPI_SPEC: &str = include_str!("../../../openapi.yaml");
 const SWAGGER_UI_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
@@ -34,6 +46,25 @@ const SWAGGER_UI_HTML: &str = r#"<!DOCTYPE html>
 </body>
 </html>"#;
 
+/// Shared state of the request handlers
+#[derive(Clone)]
+struct AppState {
+    jobs: Jobs,
+    plans: Plans,
+}
+
+impl FromRef<AppState> for Jobs {
+    fn from_ref(state: &AppState) -> Self {
+        state.jobs.clone()
+    }
+}
+
+impl FromRef<AppState> for Plans {
+    fn from_ref(state: &AppState) -> Self {
+        state.plans.clone()
+    }
+}
+
 #[tokio::main]
 async fn main() {
     // Initialize tracing
@@ -41,15 +72,54 @@ async fn main() {
 
     info!("Starting Cutting Optimizer API");
 
+    // Number of optimization jobs that may run at the same time
+    let max_concurrent_jobs = std::env::var("MAX_CONCURRENT_JOBS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+    info!("Running up to {} jobs at once", max_concurrent_jobs);
+
+    // Every finished plan is kept in this SQLite database
+    let plans_db = std::env::var("PLANS_DB").unwrap_or_else(|_| "plans.db".to_string());
+    let plans = Plans::open(&plans_db).expect("Failed to open plan database");
+    info!("Storing plans in {}", plans_db);
+
     // Build application
     let app = Router::new()
         .route("/", get(serve_ui))
         .route("/api/health", get(health_check))
         .route("/api/optimize", post(optimize))
+        .route("/api/validate", post(validate_request))
+        .route("/api/verify", post(verify_result))
+        .route("/api/edit", post(edit_result))
+        .route("/api/import", post(import_items))
+        .route("/api/jobs", post(jobs::create_job))
+        .route("/api/jobs/:id", get(jobs::get_job).delete(jobs::delete_job))
+        .route("/api/jobs/:id/events", get(jobs::job_events))
+        .route("/api/plans", get(plans::list_plans))
+        .route(
+            "/api/plans/:id",
+            get(plans::get_plan).delete(plans::delete_plan),
+        )
+        .route("/api/plans/:id/svg", get(plans::plan_svg))
+        .route("/api/plans/:id/pdf", get(plans::plan_pdf))
+        .route("/api/plans/:id/dxf", get(plans::plan_dxf))
+        .route("/api/plans/:id/export", get(plans::plan_export))
         .route("/api/generate/svg", post(generate_svg))
+        .route("/api/generate/pdf", post(generate_pdf))
+        .route("/api/generate/dxf", post(generate_dxf))
+        .route("/api/generate/export", post(generate_export))
         .route("/openapi.yaml", get(serve_openapi_spec))
         .route("/docs", get(serve_swagger_ui))
-        .layer(CorsLayer::permissive());
+        .layer(CorsLayer::permissive())
+        .with_state(AppState {
+            jobs: Jobs::new(max_concurrent_jobs, plans.clone()),
+            plans,
+        });
 
     // Start server
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
@@ -71,214 +141,402 @@ async fn health_check() -> Json<serde_json::Value> {
     }))
 }
 
-/// Main optimization endpoint
+/// Main optimization endpoint. The optimization runs on the job worker pool,
+/// and the result is stored as a plan whose id is returned in the
+/// `X-Plan-Id` header. If storing fails, the result is still returned,
+/// without the header.
 async fn optimize(
+    State(jobs): State<Jobs>,
+    State(plans): State<Plans>,
+    Query(meta): Query<PlanMeta>,
     Json(request): Json<OptimizationRequest>,
-) -> Result<Json<OptimizationResult>, AppError> {
+) -> Result<Response, AppError> {
     info!(
         "Received optimization request with {} items and {} panel types",
         request.items.len(),
         request.panel_types.len()
     );
 
-    let optimizer = Optimizer::new(request)?;
-    let result = optimizer.optimize()?;
+    let optimizer = Optimizer::new(request.clone())?;
+    let result = jobs.run(optimizer).await?;
 
     info!(
         "Optimization complete: {} panels required, {:.2}% waste",
         result.summary.total_panels, result.summary.waste_percentage
     );
 
+    let id = uuid::Uuid::new_v4().to_string();
+    if let Err(err) = plans.save(&id, &meta, &request, &result).await {
+        error!("Failed to store plan {}: {}", id, err);
+        return Ok(Json(result).into_response());
+    }
+
+    Ok(([("X-Plan-Id", id)], Json(result)).into_response())
+}
+
+/// Checks a request without optimizing it and reports every error and warning
+async fn validate_request(Json(request): Json<OptimizationRequest>) -> Json<ValidationReport> {
+    let report = validate(&request);
+    info!(
+        "Validated request: {} errors, {} warnings",
+        report.errors.len(),
+        report.warnings.len()
+    );
+
+    Json(report)
+}
+
+/// Request and result checked by `POST /api/verify`
+#[derive(Deserialize)]
+struct VerifyRequest {
+    request: OptimizationRequest,
+    result: OptimizationResult,
+}
+
+/// Checks a result against its request and lists every violation
+async fn verify_result(Json(body): Json<VerifyRequest>) -> Json<Vec<Violation>> {
+    let violations = verify(&body.request, &body.result);
+    info!("Verified result: {} violations", violations.len());
+
+    Json(violations)
+}
+
+/// Reads the items of an uploaded cut list (multipart field `file`), with the
+/// column headers and file settings of the query
+async fn import_items(
+    Query(options): Query<CutListOptions>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<Item>>, AppError> {
+    let mut data = None;
+    while let Some(field) = multipart.next_field().await.map_err(upload_error)? {
+        if field.name() == Some("file") {
+            data = Some(field.bytes().await.map_err(upload_error)?);
+        }
+    }
+    let Some(data) = data else {
+        return Err(OptimizerError::InvalidInput(vec![FieldError {
+            field: "file".into(),
+            message: "Upload the cut list as multipart field 'file'".into(),
+        }])
+        .into());
+    };
+
+    let items = import_cut_list(&data, &options)?;
+    info!("Imported {} items from a cut list", items.len());
+
+    Ok(Json(items))
+}
+
+fn upload_error(err: MultipartError) -> AppError {
+    OptimizerError::InvalidInput(vec![FieldError {
+        field: "file".into(),
+        message: format!("Cannot read the upload: {}", err.body_text()),
+    }])
+    .into()
+}
+
+/// Request, result and edit taken by `POST /api/edit`
+#[derive(Deserialize)]
+struct EditRequest {
+    request: OptimizationRequest,
+    result: OptimizationResult,
+    edit: LayoutEdit,
+}
+
+/// Applies a manual edit to a result and returns the updated result
+async fn edit_result(Json(body): Json<EditRequest>) -> Result<Json<OptimizationResult>, AppError> {
+    let optimizer = Optimizer::new(body.request)?;
+    let result = optimizer.apply_edit(&body.result, &body.edit)?;
+    info!("Applied edit {:?}", body.edit);
+
     Ok(Json(result))
 }
 
+/// Drawing options of the SVG endpoints, passed as query parameters
+/// (`?width=1200&dimensions=true&colors=shelf:%232563eb`)
+#[derive(Debug, Default, Deserialize)]
+pub struct SvgQuery {
+    /// Millimetres per SVG unit
+    pub scale: Option<f64>,
+    /// Width in SVG units to draw the widest panel at (overrides `scale`)
+    pub width: Option<f64>,
+    /// Comma-separated `id:color` pairs
+    pub colors: Option<String>,
+    #[serde(default)]
+    pub dimensions: bool,
+    /// Width of the saw cuts to draw (mm)
+    pub kerf: Option<f64>,
+    #[serde(default)]
+    pub trimming: bool,
+    pub unused_areas: Option<bool>,
+    pub legend: Option<bool>,
+}
+
+impl SvgQuery {
+    fn options(&self) -> SvgOptions {
+        let defaults = SvgOptions::default();
+        SvgOptions {
+            scale: self.scale.unwrap_or(defaults.scale),
+            target_width: self.width,
+            item_colors: self
+                .colors
+                .iter()
+                .flat_map(|colors| colors.split(','))
+                .filter_map(|pair| pair.split_once(':'))
+                .map(|(id, color)| (id.trim().to_string(), color.trim().to_string()))
+                .collect(),
+            dimensions: self.dimensions,
+            kerf: self.kerf.unwrap_or(defaults.kerf),
+            trimming: self.trimming,
+            unused_areas: self.unused_areas.unwrap_or(defaults.unused_areas),
+            legend: self.legend.unwrap_or(defaults.legend),
+        }
+    }
+}
+
 /// Generate SVG visualization
-async fn generate_svg(Json(result): Json<OptimizationResult>) -> Result<Response, AppError> {
+async fn generate_svg(
+    Query(query): Query<SvgQuery>,
+    Json(result): Json<OptimizationResult>,
+) -> Result<Response, AppError> {
     info!("Generating SVG for {} panels", result.layouts.len());
 
-    let svg = generate_svg_content(&result)?;
+    let svg = render_svg(&result, &query.options())?;
 
     Ok((StatusCode::OK, [("Content-Type", "image/svg+xml")], svg).into_response())
 }
 
-/// Generate SVG content from optimization result
-fn generate_svg_content(result: &OptimizationResult) -> Result<String, AppError> {
-    use std::fmt::Write;
-
-    let mut svg = String::new();
-    let margin = 20.0;
-    let scale = 2.0; // Scale down panels to fit in SVG
-    let panel_spacing = 40.0;
-
-    // Calculate total SVG size
-    let max_width = result.layouts.iter().map(|l| l.width).fold(0.0, f64::max);
-    let total_height: f64 = result
-        .layouts
-        .iter()
-        .map(|l| l.height + panel_spacing)
-        .sum();
-
-    let svg_width = (max_width / scale) + (2.0 * margin);
-    let svg_height = (total_height / scale) + (2.0 * margin) + 30.0; // Extra space for legend
-
-    // SVG header
-    writeln!(&mut svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
-    writeln!(
-        &mut svg,
-        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
-        svg_width, svg_height, svg_width, svg_height
-    )
-    .unwrap();
+async fn generate_pdf(
+    Query(options): Query<PdfOptions>,
+    Json(result): Json<OptimizationResult>,
+) -> Response {
+    info!("Generating PDF for {} panels", result.layouts.len());
 
-    // Background
-    writeln!(
-        &mut svg,
-        r##"  <rect width="100%" height="100%" fill="#f5f5f5"/>"##
-    )
-    .unwrap();
-
-    let mut y_offset = margin;
-
-    for layout in &result.layouts {
-        let x = margin;
-        let panel_width = layout.width / scale;
-        let panel_height = layout.height / scale;
-
-        // Draw panel background
-        writeln!(&mut svg, r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#ffffff" stroke="#333" stroke-width="2"/>"##,
-                 x, y_offset, panel_width, panel_height).unwrap();
-
-        // Draw panel label
-        writeln!(&mut svg, r##"  <text x="{}" y="{}" font-family="Arial" font-size="14" fill="#333">{} #{}</text>"##,
-                 x, y_offset - 5.0, layout.panel_type_id, layout.panel_number).unwrap();
-
-        // Draw placements
-        for placement in &layout.placements {
-            let px = x + (placement.x / scale);
-            let py = y_offset + (placement.y / scale);
-            let pw = placement.width / scale;
-            let ph = placement.height / scale;
-
-            // Draw item rectangle
-            writeln!(&mut svg, r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#4CAF50" stroke="#2E7D32" stroke-width="1" opacity="0.7"/>"##,
-                     px, py, pw, ph).unwrap();
-
-            // Draw item label
-            let label = if placement.rotated {
-                format!("{} (R)", placement.item_id)
-            } else {
-                placement.item_id.clone()
-            };
-
-            writeln!(&mut svg, r##"  <text x="{}" y="{}" font-family="Arial" font-size="10" fill="#fff" text-anchor="middle">{}</text>"##,
-                     px + pw / 2.0, py + ph / 2.0 + 3.0, label).unwrap();
-        }
+    let pdf = render_pdf(&result, &options);
 
-        // Draw unused areas (waste regions)
-        for unused in &layout.unused_areas {
-            let ux = x + (unused.x / scale);
-            let uy = y_offset + (unused.y / scale);
-            let uw = unused.width / scale;
-            let uh = unused.height / scale;
-
-            // Draw unused area with diagonal hatch pattern
-            writeln!(&mut svg, r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#fee2e2" stroke="#ef4444" stroke-width="1" stroke-dasharray="4,2" opacity="0.6"/>"##,
-                     ux, uy, uw, uh).unwrap();
-
-            // Only show label if area is large enough
-            if uw > 40.0 && uh > 20.0 {
-                let area_sqm = (unused.width * unused.height) / 1_000_000.0;
-                let label = format!("{:.2}m²", area_sqm);
-                writeln!(&mut svg, r##"  <text x="{}" y="{}" font-family="Arial" font-size="9" fill="#dc2626" text-anchor="middle" opacity="0.8">{}</text>"##,
-                         ux + uw / 2.0, uy + uh / 2.0 + 3.0, label).unwrap();
-            }
+    (StatusCode::OK, [("Content-Type", "application/pdf")], pdf).into_response()
+}
+
+async fn generate_dxf(
+    Query(options): Query<DxfOptions>,
+    Json(result): Json<OptimizationResult>,
+) -> Response {
+    info!("Generating DXF for {} panels", result.layouts.len());
+
+    let dxf = render_dxf(&result, &options);
+
+    (StatusCode::OK, [("Content-Type", "image/vnd.dxf")], dxf).into_response()
+}
+
+/// File format of the table export endpoints
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+/// Options of the table export endpoints, passed as query parameters
+/// (`?format=csv&table=totals`); `table` only applies to CSV, XLSX files
+/// hold every table as a worksheet
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportQuery {
+    pub format: ExportFormat,
+    pub table: ExportTable,
+}
+
+impl ExportQuery {
+    pub(crate) fn export(&self, result: &OptimizationResult) -> Result<Response, AppError> {
+        let response = match self.format {
+            ExportFormat::Csv => (
+                StatusCode::OK,
+                [("Content-Type", "text/csv")],
+                result_to_csv(result, self.table)?,
+            )
+                .into_response(),
+            ExportFormat::Xlsx => (
+                StatusCode::OK,
+                [(
+                    "Content-Type",
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                )],
+                result_to_xlsx(result)?,
+            )
+                .into_response(),
+        };
+        Ok(response)
+    }
+}
+
+/// Export placements, panels and per-type totals as CSV or XLSX
+async fn generate_export(
+    Query(query): Query<ExportQuery>,
+    Json(result): Json<OptimizationResult>,
+) -> Result<Response, AppError> {
+    info!("Exporting tables for {} panels", result.layouts.len());
+
+    query.export(&result)
+}
+
+/// Error body of every failed API request. `code` is machine-readable; the
+/// remaining fields depend on it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: ApiErrorCode,
+    /// First offending request field (`invalid_input`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Every invalid field with its message (`invalid_input`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+    /// Items that fit no panel type (`cannot_fit`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<ItemSize>>,
+    /// Panel type with the largest usable area (`cannot_fit`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub largest_panel: Option<PanelSize>,
+    /// Items left over when stock ran out (`insufficient_stock`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unplaced_items: Option<Vec<String>>,
+    /// Problems the edit would cause (`invalid_edit`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violations: Option<Vec<Violation>>,
+}
+
+/// Error codes of the optimizer plus the ones only the API produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCode {
+    InvalidInput,
+    CannotFit,
+    InsufficientStock,
+    NotGuillotine,
+    InvalidEdit,
+    ExportFailed,
+    NotFound,
+    /// Job cancelled before it produced a result
+    Cancelled,
+    Internal,
+}
+
+impl From<ErrorCode> for ApiErrorCode {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::InvalidInput => ApiErrorCode::InvalidInput,
+            ErrorCode::CannotFit => ApiErrorCode::CannotFit,
+            ErrorCode::InsufficientStock => ApiErrorCode::InsufficientStock,
+            ErrorCode::NotGuillotine => ApiErrorCode::NotGuillotine,
+            ErrorCode::InvalidEdit => ApiErrorCode::InvalidEdit,
+            ErrorCode::ExportFailed => ApiErrorCode::ExportFailed,
         }
+    }
+}
 
-        y_offset += panel_height + panel_spacing;
+impl ErrorResponse {
+    pub fn new(code: ApiErrorCode, error: String) -> Self {
+        Self {
+            error,
+            code,
+            field: None,
+            errors: None,
+            items: None,
+            largest_panel: None,
+            unplaced_items: None,
+            violations: None,
+        }
     }
 
-    // Legend
-    let legend_y = svg_height - margin - 5.0;
-    writeln!(
-        &mut svg,
-        r##"  <rect x="{}" y="{}" width="12" height="12" fill="#4CAF50" stroke="#2E7D32" stroke-width="1" opacity="0.7"/>"##,
-        margin, legend_y
-    )
-    .unwrap();
-    writeln!(
-        &mut svg,
-        r##"  <text x="{}" y="{}" font-family="Arial" font-size="11" fill="#333">Placed items</text>"##,
-        margin + 16.0, legend_y + 10.0
-    )
-    .unwrap();
-    writeln!(
-        &mut svg,
-        r##"  <rect x="{}" y="{}" width="12" height="12" fill="#fee2e2" stroke="#ef4444" stroke-width="1" stroke-dasharray="4,2" opacity="0.6"/>"##,
-        margin + 100.0, legend_y
-    )
-    .unwrap();
-    writeln!(
-        &mut svg,
-        r##"  <text x="{}" y="{}" font-family="Arial" font-size="11" fill="#333">Unused areas</text>"##,
-        margin + 116.0, legend_y + 10.0
-    )
-    .unwrap();
-
-    // Summary
-    writeln!(
-        &mut svg,
-        r##"  <text x="{}" y="{}" font-family="Arial" font-size="12" fill="#666">"##,
-        margin + 250.0,
-        legend_y + 10.0
-    )
-    .unwrap();
-    writeln!(
-        &mut svg,
-        r#"    Panels: {} | Waste: {:.1}%"#,
-        result.summary.total_panels, result.summary.waste_percentage
-    )
-    .unwrap();
-    writeln!(&mut svg, r#"  </text>"#).unwrap();
+    fn status(&self) -> StatusCode {
+        match self.code {
+            ApiErrorCode::InvalidInput
+            | ApiErrorCode::CannotFit
+            | ApiErrorCode::InsufficientStock
+            | ApiErrorCode::NotGuillotine => StatusCode::BAD_REQUEST,
+            ApiErrorCode::InvalidEdit => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ApiErrorCode::Cancelled => StatusCode::CONFLICT,
+            ApiErrorCode::ExportFailed | ApiErrorCode::Internal => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
 
-    writeln!(&mut svg, "</svg>").unwrap();
+impl From<&OptimizerError> for ErrorResponse {
+    fn from(err: &OptimizerError) -> Self {
+        let mut response = Self::new(err.code().into(), err.to_string());
+        match err {
+            OptimizerError::CannotFitAll {
+                items,
+                largest_panel,
+            } => {
+                response.items = Some(items.clone());
+                response.largest_panel = largest_panel.clone();
+            }
+            OptimizerError::InsufficientStock { unplaced_items } => {
+                response.unplaced_items = Some(unplaced_items.clone());
+            }
+            OptimizerError::InvalidInput(errors) => {
+                response.field = errors.first().map(|e| e.field.clone());
+                response.errors = Some(errors.clone());
+            }
+            OptimizerError::InvalidEdit(violations) => {
+                response.violations = Some(violations.clone());
+            }
+            OptimizerError::NotGuillotine | OptimizerError::Export(_) => {}
+        }
+        response
+    }
+}
 
-    Ok(svg)
+impl IntoResponse for ErrorResponse {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self)).into_response()
+    }
 }
 
 /// Application error type
-struct AppError(anyhow::Error);
+#[derive(Debug)]
+struct AppError(Box<ErrorResponse>);
+
+impl AppError {
+    fn not_found(message: String) -> Self {
+        AppError(Box::new(ErrorResponse::new(
+            ApiErrorCode::NotFound,
+            message,
+        )))
+    }
+
+    fn internal(message: String) -> Self {
+        AppError(Box::new(ErrorResponse::new(
+            ApiErrorCode::Internal,
+            message,
+        )))
+    }
+}
 
 impl From<OptimizerError> for AppError {
     fn from(err: OptimizerError) -> Self {
-        AppError(err.into())
+        AppError(Box::new(ErrorResponse::from(&err)))
     }
 }
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
-        AppError(err)
+        AppError(Box::new(ErrorResponse::new(
+            ApiErrorCode::Internal,
+            err.to_string(),
+        )))
     }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        error!("Request error: {}", self.0);
-
-        let message = self.0.to_string();
-        let status =
-            if message.contains("Cannot fit all items") || message.contains("Invalid input") {
-                StatusCode::BAD_REQUEST
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            };
-
-        (
-            status,
-            Json(json!({
-                "error": message,
-            })),
-        )
-            .into_response()
+        error!("Request error: {}", self.0.error);
+        self.0.into_response()
     }
 }
 
@@ -300,6 +558,15 @@ async fn serve_ui() -> impl IntoResponse {
                 <ul>
                     <li>GET /api/health - Health check</li>
                     <li>POST /api/optimize - Run optimization</li>
+                    <li>POST /api/validate - Check a request for errors and warnings</li>
+                    <li>POST /api/jobs - Queue an optimization job</li>
+                    <li>GET /api/jobs/{id} - Job status and result</li>
+                    <li>DELETE /api/jobs/{id} - Cancel a job</li>
+                    <li>GET /api/jobs/{id}/events - Stream job progress (SSE)</li>
+                    <li>GET /api/plans - List stored plans (filter with ?tag=)</li>
+                    <li>GET /api/plans/{id} - Stored plan with request and result</li>
+                    <li>DELETE /api/plans/{id} - Delete a stored plan</li>
+                    <li>GET /api/plans/{id}/svg - Render a stored plan as SVG</li>
                     <li>POST /api/generate/svg - Generate SVG visualization</li>
                 </ul>
             </body>
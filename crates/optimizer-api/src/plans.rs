@@ -0,0 +1,305 @@
+use crate::{AppError, ExportQuery, SvgQuery};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use optimizer_core::{
+    render_dxf, render_pdf, render_svg, DxfOptions, OptimizationRequest, OptimizationResult,
+    PdfOptions, Summary,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS plans (
+    id TEXT PRIMARY KEY,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    name TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',
+    request TEXT NOT NULL,
+    result TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS plans_created_at ON plans (created_at);
+";
+
+/// Name and tags a client attaches to a stored plan, passed as query
+/// parameters (`?name=Kitchen&tags=site-a,monday`)
+#[derive(Debug, Default, Deserialize)]
+pub struct PlanMeta {
+    pub name: Option<String>,
+    /// Comma-separated tags
+    pub tags: Option<String>,
+}
+
+impl PlanMeta {
+    fn tags(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Stored plan without request and result, as listed by `GET /api/plans`
+#[derive(Debug, Serialize)]
+pub struct PlanInfo {
+    pub id: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub summary: Summary,
+}
+
+/// Stored plan as returned by `GET /api/plans/{id}`
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub id: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub request: OptimizationRequest,
+    pub result: OptimizationResult,
+}
+
+/// On-disk store (SQLite) of every optimization request and result served by
+/// the API, so finished plans can be looked up again later.
+#[derive(Clone)]
+pub struct Plans {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl Plans {
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute_batch(SCHEMA)?;
+        Ok(Self {
+            db: Arc::new(Mutex::new(db)),
+        })
+    }
+
+    /// Runs a query on tokio's blocking thread pool, so neither a slow query
+    /// nor a contended lock stalls a runtime worker.
+    async fn with_db<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&Connection) -> anyhow::Result<T> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || query(&db.lock().unwrap())).await?
+    }
+
+    /// Stores a finished optimization under the given id.
+    pub async fn save(
+        &self,
+        id: &str,
+        meta: &PlanMeta,
+        request: &OptimizationRequest,
+        result: &OptimizationResult,
+    ) -> anyhow::Result<()> {
+        let id = id.to_string();
+        let name = meta.name.clone();
+        let tags = serde_json::to_string(&meta.tags())?;
+        let request = serde_json::to_string(request)?;
+        let result = serde_json::to_string(result)?;
+        self.with_db(move |db| {
+            db.execute(
+                "INSERT INTO plans (id, name, tags, request, result) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, name, tags, request, result],
+            )?;
+            info!("Stored plan {}", id);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Lists stored plans, newest first, optionally only those with a tag.
+    async fn list(&self, tag: Option<String>, limit: u32) -> anyhow::Result<Vec<PlanInfo>> {
+        self.with_db(move |db| {
+            let mut statement = db.prepare(
+                "SELECT id, created_at, name, tags, json_extract(result, '$.summary') FROM plans
+                 WHERE ?1 IS NULL OR EXISTS (SELECT 1 FROM json_each(plans.tags) WHERE value = ?1)
+                 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+            )?;
+            let rows = statement.query_map(params![tag, limit], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?;
+
+            let mut plans = Vec::new();
+            for row in rows {
+                let (id, created_at, name, tags, summary) = row?;
+                plans.push(PlanInfo {
+                    id,
+                    created_at,
+                    name,
+                    tags: serde_json::from_str(&tags)?,
+                    summary: serde_json::from_str(&summary)?,
+                });
+            }
+            Ok(plans)
+        })
+        .await
+    }
+
+    async fn get(&self, id: &str) -> anyhow::Result<Option<Plan>> {
+        let id = id.to_string();
+        self.with_db(move |db| {
+            let row = db
+                .query_row(
+                    "SELECT created_at, name, tags, request, result FROM plans WHERE id = ?1",
+                    params![id],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, Option<String>>(1)?,
+                            row.get::<_, String>(2)?,
+                            row.get::<_, String>(3)?,
+                            row.get::<_, String>(4)?,
+                        ))
+                    },
+                )
+                .optional()?;
+
+            let Some((created_at, name, tags, request, result)) = row else {
+                return Ok(None);
+            };
+            Ok(Some(Plan {
+                id,
+                created_at,
+                name,
+                tags: serde_json::from_str(&tags)?,
+                request: serde_json::from_str(&request)?,
+                result: serde_json::from_str(&result)?,
+            }))
+        })
+        .await
+    }
+
+    /// Returns false if there was no plan with this id.
+    async fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        let id = id.to_string();
+        self.with_db(move |db| {
+            let deleted = db.execute("DELETE FROM plans WHERE id = ?1", params![id])?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+}
+
+fn plan_not_found(id: &str) -> AppError {
+    AppError::not_found(format!("Plan '{}' not found", id))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    /// Only plans carrying this tag
+    pub tag: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Lists stored plans, newest first
+pub async fn list_plans(
+    State(plans): State<Plans>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<PlanInfo>>, AppError> {
+    let list = plans.list(query.tag, query.limit.unwrap_or(100)).await?;
+    Ok(Json(list))
+}
+
+/// Returns a stored plan with its request and result
+pub async fn get_plan(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+) -> Result<Json<Plan>, AppError> {
+    plans
+        .get(&id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| plan_not_found(&id))
+}
+
+/// Deletes a stored plan
+pub async fn delete_plan(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !plans.delete(&id).await? {
+        return Err(plan_not_found(&id));
+    }
+    info!("Deleted plan {}", id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Renders a stored result as SVG, with the drawing options of `POST /api/generate/svg`
+pub async fn plan_svg(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+    Query(query): Query<SvgQuery>,
+) -> Result<Response, AppError> {
+    let Some(plan) = plans.get(&id).await? else {
+        return Err(plan_not_found(&id));
+    };
+    let svg = render_svg(&plan.result, &query.options())?;
+
+    Ok((StatusCode::OK, [("Content-Type", "image/svg+xml")], svg).into_response())
+}
+
+/// Renders a stored plan as a printable PDF, titled with the plan name
+/// unless the query sets a title
+pub async fn plan_pdf(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+    Query(mut options): Query<PdfOptions>,
+) -> Result<Response, AppError> {
+    let Some(plan) = plans.get(&id).await? else {
+        return Err(plan_not_found(&id));
+    };
+    if options.title.is_none() {
+        options.title = plan.name;
+    }
+    let pdf = render_pdf(&plan.result, &options);
+
+    Ok((StatusCode::OK, [("Content-Type", "application/pdf")], pdf).into_response())
+}
+
+/// Exports a stored result as DXF, with the units of `POST /api/generate/dxf`
+pub async fn plan_dxf(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+    Query(options): Query<DxfOptions>,
+) -> Result<Response, AppError> {
+    let Some(plan) = plans.get(&id).await? else {
+        return Err(plan_not_found(&id));
+    };
+    let dxf = render_dxf(&plan.result, &options);
+
+    Ok((StatusCode::OK, [("Content-Type", "image/vnd.dxf")], dxf).into_response())
+}
+
+/// Exports a stored result as CSV or XLSX, with the options of `POST /api/generate/export`
+pub async fn plan_export(
+    State(plans): State<Plans>,
+    Path(id): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let Some(plan) = plans.get(&id).await? else {
+        return Err(plan_not_found(&id));
+    };
+    query.export(&plan.result)
+}
+
+#[cfg(test)]
+mod tests;
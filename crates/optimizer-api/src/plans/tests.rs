@@ -0,0 +1,139 @@
+use super::*;
+use optimizer_core::{Item, OptimizationResult, Optimizer, PanelType};
+
+fn request(item_count: u32) -> OptimizationRequest {
+    OptimizationRequest {
+        cut_width: 5.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "shelf".into(),
+            width: 600.0,
+            height: 300.0,
+            quantity: item_count,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn optimize(request: &OptimizationRequest) -> OptimizationResult {
+    Optimizer::new(request.clone()).unwrap().optimize().unwrap()
+}
+
+fn meta(name: &str, tags: &str) -> PlanMeta {
+    PlanMeta {
+        name: Some(name.into()),
+        tags: Some(tags.into()),
+    }
+}
+
+fn ids(plans: &[PlanInfo]) -> Vec<&str> {
+    plans.iter().map(|plan| plan.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_plans_round_trip() {
+    let plans = Plans::open(":memory:").unwrap();
+    let kitchen = request(4);
+    let kitchen_result = optimize(&kitchen);
+    plans
+        .save(
+            "kitchen",
+            &meta("Kitchen", "site-a, monday"),
+            &kitchen,
+            &kitchen_result,
+        )
+        .await
+        .unwrap();
+    plans
+        .save(
+            "hall",
+            &meta("Hall", "site-b"),
+            &request(2),
+            &optimize(&request(2)),
+        )
+        .await
+        .unwrap();
+    plans
+        .save(
+            "attic",
+            &PlanMeta::default(),
+            &request(9),
+            &optimize(&request(9)),
+        )
+        .await
+        .unwrap();
+
+    // Newest first, optionally by tag and limited
+    assert_eq!(
+        ids(&plans.list(None, 100).await.unwrap()),
+        ["attic", "hall", "kitchen"]
+    );
+    assert_eq!(ids(&plans.list(None, 2).await.unwrap()), ["attic", "hall"]);
+    let site_a = plans.list(Some("site-a".into()), 100).await.unwrap();
+    assert_eq!(ids(&site_a), ["kitchen"]);
+    assert_eq!(site_a[0].name.as_deref(), Some("Kitchen"));
+    assert_eq!(site_a[0].tags, ["site-a", "monday"]);
+    assert_eq!(
+        site_a[0].summary.total_panels,
+        kitchen_result.summary.total_panels
+    );
+    assert!(plans
+        .list(Some("site-c".into()), 100)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let plan = plans.get("kitchen").await.unwrap().unwrap();
+    assert_eq!(plan.name.as_deref(), Some("Kitchen"));
+    assert_eq!(plan.tags, ["site-a", "monday"]);
+    assert_eq!(plan.request.items[0].quantity, 4);
+    assert_eq!(
+        serde_json::to_value(&plan.result).unwrap(),
+        serde_json::to_value(&kitchen_result).unwrap()
+    );
+    assert!(plans.get("garage").await.unwrap().is_none());
+
+    assert!(plans.delete("kitchen").await.unwrap());
+    assert!(!plans.delete("kitchen").await.unwrap());
+    assert!(plans.get("kitchen").await.unwrap().is_none());
+    assert_eq!(
+        ids(&plans.list(None, 100).await.unwrap()),
+        ["attic", "hall"]
+    );
+}
+
+#[tokio::test]
+async fn test_optimize_returns_result_when_plan_cannot_be_stored() {
+    let plans = Plans::open(":memory:").unwrap();
+    plans
+        .db
+        .lock()
+        .unwrap()
+        .execute_batch("DROP TABLE plans")
+        .unwrap();
+    let jobs = crate::jobs::Jobs::new(1, plans.clone());
+
+    let response = crate::optimize(
+        State(jobs),
+        State(plans),
+        Query(PlanMeta::default()),
+        Json(request(4)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("X-Plan-Id").is_none());
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let result: OptimizationResult = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result.summary.total_panels, 1);
+}
@@ -1,8 +1,11 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::*;
-use optimizer_core::{OptimizationRequest, Optimizer};
-use std::path::PathBuf;
+use optimizer_core::{
+    CutListOptions, DxfOptions, DxfUnits, ExportTable, Item, OptimizationRequest, Optimizer,
+    OptimizerError, PageSize, PdfOptions, SvgOptions,
+};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "optimizer")]
@@ -23,6 +26,49 @@ enum Commands {
         /// Output file for result (JSON)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Cut list (CSV, XLSX, XLS or ODS) whose parts are added to the input items
+        #[arg(long)]
+        cut_list: Option<PathBuf>,
+
+        #[command(flatten)]
+        columns: CutListArgs,
+    },
+
+    /// Turn a CSV or spreadsheet cut list into an optimization request
+    Import {
+        /// Cut list (CSV, XLSX, XLS or ODS)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Request (YAML or JSON) with the panel types; the parts are added to its items
+        #[arg(short, long)]
+        request: Option<PathBuf>,
+
+        /// Output request file (YAML or JSON by extension; prints JSON when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        columns: CutListArgs,
+    },
+
+    /// Check an input file and list every error and warning
+    Validate {
+        /// Input file (YAML or JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+    },
+
+    /// Check a result against its request and list every violation
+    Verify {
+        /// Request file (YAML or JSON)
+        #[arg(short, long)]
+        request: PathBuf,
+
+        /// Result file (JSON)
+        #[arg(short = 'R', long)]
+        result: PathBuf,
     },
 
     /// Generate SVG visualization from result
@@ -31,39 +77,343 @@ enum Commands {
         #[arg(short, long)]
         input: PathBuf,
 
-        /// Output SVG file
+        /// Output SVG file (a directory with --per-panel)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Millimetres per SVG unit
+        #[arg(long, default_value_t = 2.0)]
+        scale: f64,
+
+        /// Width in SVG units to draw the widest panel at (overrides --scale)
+        #[arg(long)]
+        width: Option<f64>,
+
+        /// Fill color of an item, such as shelf=#2563eb (repeatable)
+        #[arg(long = "color", value_name = "ID=COLOR", value_parser = parse_item_color)]
+        colors: Vec<(String, String)>,
+
+        /// Label every part with its size
+        #[arg(long)]
+        dimensions: bool,
+
+        /// Draw saw cuts of this width (mm)
+        #[arg(long, default_value_t = 0.0)]
+        kerf: f64,
+
+        /// Shade the trimmed panel borders
+        #[arg(long)]
+        trimming: bool,
+
+        /// Leave out the unused areas
+        #[arg(long)]
+        no_unused_areas: bool,
+
+        /// Leave out the legend and summary line
+        #[arg(long)]
+        no_legend: bool,
+
+        /// Write one SVG file per panel into the output directory
+        #[arg(long)]
+        per_panel: bool,
+    },
+
+    /// Export a result as a printable PDF with one page per panel
+    Pdf {
+        /// Input result file (JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output PDF file
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Paper size
+        #[arg(long, value_enum, default_value_t = Paper::A4)]
+        paper: Paper,
+
+        /// Print pages upright instead of sideways
+        #[arg(long)]
+        portrait: bool,
+
+        /// Heading of the cover page
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Export panel layouts as DXF for CNC routers and CAD software
+    Dxf {
+        /// Input result file (JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output DXF file (a directory with --per-panel)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Drawing unit; coordinates are converted from millimetres ($INSUNITS is only a hint)
+        #[arg(long, value_enum, default_value_t = Units::Mm)]
+        units: Units,
+
+        /// Write one DXF file per panel into the output directory
+        #[arg(long)]
+        per_panel: bool,
+    },
+
+    /// Export reusable remnants of a result as an inventory list
+    Remnants {
+        /// Input result file (JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Minimum remnant area to keep (same units as width x height)
+        #[arg(long, default_value_t = 0.0)]
+        min_size: f64,
+
+        /// Output format (defaults to the output file extension, else JSON)
+        #[arg(short, long, value_enum)]
+        format: Option<InventoryFormat>,
+    },
+
+    /// Export placements, panels and per-type totals as flat tables
+    Export {
+        /// Input result file (JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output CSV or XLSX file
         #[arg(short, long)]
         output: PathBuf,
+
+        /// Output format (defaults to the output file extension, else CSV)
+        #[arg(short, long, value_enum)]
+        format: Option<TableFormat>,
+
+        /// Table to write to a CSV file (XLSX files get one worksheet per table)
+        #[arg(long, value_enum, default_value_t = Table::Placements)]
+        table: Table,
     },
 }
 
+/// Column headers of a cut list (matched case-insensitively)
+#[derive(Args)]
+struct CutListArgs {
+    /// Header of the item id column [default: id]
+    #[arg(long)]
+    id_column: Option<String>,
+
+    /// Header of the part length column, read as item width [default: length]
+    #[arg(long)]
+    length_column: Option<String>,
+
+    /// Header of the part width column, read as item height [default: width]
+    #[arg(long)]
+    width_column: Option<String>,
+
+    /// Header of the quantity column [default: quantity]
+    #[arg(long)]
+    quantity_column: Option<String>,
+
+    /// Header of the rotation column: yes/no, true/false, y/n, 1/0 or x for yes [default: rotation]
+    #[arg(long)]
+    rotation_column: Option<String>,
+
+    /// Header of the material column [default: material]
+    #[arg(long)]
+    material_column: Option<String>,
+
+    /// Header of the notes column [default: notes]
+    #[arg(long)]
+    notes_column: Option<String>,
+
+    /// CSV field delimiter (detected from the header row when omitted)
+    #[arg(long)]
+    delimiter: Option<char>,
+
+    /// Worksheet to read from a spreadsheet (default: the first)
+    #[arg(long)]
+    sheet: Option<String>,
+}
+
+impl CutListArgs {
+    fn options(&self) -> CutListOptions {
+        let defaults = CutListOptions::default();
+        let column = |arg: &Option<String>, default: String| arg.clone().unwrap_or(default);
+        CutListOptions {
+            id_column: column(&self.id_column, defaults.id_column),
+            length_column: column(&self.length_column, defaults.length_column),
+            width_column: column(&self.width_column, defaults.width_column),
+            quantity_column: column(&self.quantity_column, defaults.quantity_column),
+            rotation_column: column(&self.rotation_column, defaults.rotation_column),
+            material_column: column(&self.material_column, defaults.material_column),
+            notes_column: column(&self.notes_column, defaults.notes_column),
+            delimiter: self.delimiter,
+            sheet: self.sheet.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Paper {
+    A4,
+    A3,
+    Letter,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Units {
+    Mm,
+    Cm,
+    M,
+    In,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InventoryFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TableFormat {
+    Csv,
+    Xlsx,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Table {
+    Placements,
+    Panels,
+    Totals,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Optimize { input, output } => {
-            optimize_command(input, output)?;
+        Commands::Optimize {
+            input,
+            output,
+            cut_list,
+            columns,
+        } => {
+            let cut_list = cut_list.map(|path| (path, columns.options()));
+            optimize_command(input, output, cut_list)?;
+        }
+        Commands::Import {
+            input,
+            request,
+            output,
+            columns,
+        } => {
+            import_command(input, request, output, &columns.options())?;
+        }
+        Commands::Validate { input } => {
+            validate_command(input)?;
+        }
+        Commands::Verify { request, result } => {
+            verify_command(request, result)?;
+        }
+        Commands::Generate {
+            input,
+            output,
+            scale,
+            width,
+            colors,
+            dimensions,
+            kerf,
+            trimming,
+            no_unused_areas,
+            no_legend,
+            per_panel,
+        } => {
+            let options = SvgOptions {
+                scale,
+                target_width: width,
+                item_colors: colors.into_iter().collect(),
+                dimensions,
+                kerf,
+                trimming,
+                unused_areas: !no_unused_areas,
+                legend: !no_legend,
+            };
+            generate_command(input, output, &options, per_panel)?;
+        }
+        Commands::Pdf {
+            input,
+            output,
+            paper,
+            portrait,
+            title,
+        } => {
+            let options = PdfOptions {
+                page_size: match paper {
+                    Paper::A4 => PageSize::A4,
+                    Paper::A3 => PageSize::A3,
+                    Paper::Letter => PageSize::Letter,
+                },
+                portrait,
+                title,
+            };
+            pdf_command(input, output, &options)?;
+        }
+        Commands::Dxf {
+            input,
+            output,
+            units,
+            per_panel,
+        } => {
+            let options = DxfOptions {
+                units: match units {
+                    Units::Mm => DxfUnits::Millimeters,
+                    Units::Cm => DxfUnits::Centimeters,
+                    Units::M => DxfUnits::Meters,
+                    Units::In => DxfUnits::Inches,
+                },
+            };
+            dxf_command(input, output, &options, per_panel)?;
         }
-        Commands::Generate { input, output } => {
-            generate_command(input, output)?;
+        Commands::Remnants {
+            input,
+            output,
+            min_size,
+            format,
+        } => {
+            remnants_command(input, output, min_size, format)?;
+        }
+        Commands::Export {
+            input,
+            output,
+            format,
+            table,
+        } => {
+            let table = match table {
+                Table::Placements => ExportTable::Placements,
+                Table::Panels => ExportTable::Panels,
+                Table::Totals => ExportTable::Totals,
+            };
+            export_command(input, output, format, table)?;
         }
     }
 
     Ok(())
 }
 
-fn optimize_command(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
+fn optimize_command(
+    input: PathBuf,
+    output: Option<PathBuf>,
+    cut_list: Option<(PathBuf, CutListOptions)>,
+) -> Result<()> {
     println!("{}", "🔍 Loading input...".bright_blue());
 
-    // Read input file
-    let content = std::fs::read_to_string(&input)?;
-    let request: OptimizationRequest = if input.extension().and_then(|s| s.to_str()) == Some("yaml")
-        || input.extension().and_then(|s| s.to_str()) == Some("yml")
-    {
-        serde_yaml::from_str(&content)?
-    } else {
-        serde_json::from_str(&content)?
-    };
+    let mut request = read_request(&input)?;
+    if let Some((path, options)) = cut_list {
+        request.items.extend(read_cut_list(&path, &options)?);
+    }
 
     println!(
         "  {} items to cut",
@@ -91,6 +441,22 @@ fn optimize_command(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
     for (panel_id, count) in &result.panels_required {
         println!("    • {}: {} panels", panel_id.bright_white(), count);
     }
+    if !result.materials.is_empty() {
+        println!();
+        println!("  By material:");
+        for group in &result.materials {
+            println!(
+                "    • {}: {} panels, {:.1}% waste",
+                group
+                    .material
+                    .as_deref()
+                    .unwrap_or("(no material)")
+                    .bright_white(),
+                group.summary.total_panels,
+                group.summary.waste_percentage
+            );
+        }
+    }
     println!();
     println!(
         "  Total panels: {}",
@@ -106,6 +472,38 @@ fn optimize_command(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
         result.summary.waste_percentage.to_string().bright_white()
     );
 
+    if let Some(total_cost) = result.summary.total_cost {
+        println!(
+            "  Total cost: {}",
+            format!("{:.2}", total_cost).bright_white()
+        );
+    }
+
+    if let (Some(lower_bound), Some(proven)) =
+        (result.summary.lower_bound, result.summary.optimality_proven)
+    {
+        let status = if proven {
+            "optimal".bright_green()
+        } else {
+            format!("gap {}", result.summary.optimality_gap.unwrap_or(0)).yellow()
+        };
+        println!(
+            "  Lower bound: {} ({})",
+            lower_bound.to_string().bright_white(),
+            status
+        );
+    }
+
+    let mut banding: Vec<_> = result.summary.banding_length.iter().collect();
+    banding.sort_by(|a, b| a.0.cmp(b.0));
+    for (material, length) in banding {
+        println!(
+            "  Edge banding {}: {}",
+            material,
+            format!("{:.0}", length).bright_white()
+        );
+    }
+
     // Display reusable remnants if available
     if let Some(reusable_area) = result.summary.reusable_remnant_area {
         println!(
@@ -121,6 +519,15 @@ fn optimize_command(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
         );
     }
 
+    // Display offcuts used
+    if !result.offcuts_used.is_empty() {
+        println!();
+        println!("  Offcuts used:");
+        for offcut in &result.offcuts_used {
+            println!("    • {}", offcut.bright_cyan());
+        }
+    }
+
     // Display optional items used
     if !result.optional_items_used.is_empty() {
         println!();
@@ -149,7 +556,143 @@ fn optimize_command(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-fn generate_command(input: PathBuf, output: PathBuf) -> Result<()> {
+/// Reads an optimization request from a YAML or JSON file
+fn read_request(input: &Path) -> Result<OptimizationRequest> {
+    let content = std::fs::read_to_string(input)?;
+    let request = if input.extension().and_then(|s| s.to_str()) == Some("yaml")
+        || input.extension().and_then(|s| s.to_str()) == Some("yml")
+    {
+        serde_yaml::from_str(&content)?
+    } else {
+        serde_json::from_str(&content)?
+    };
+    Ok(request)
+}
+
+/// Reads the items of a cut list, printing every row error
+fn read_cut_list(input: &Path, options: &CutListOptions) -> Result<Vec<Item>> {
+    let data = std::fs::read(input)?;
+    match optimizer_core::import_cut_list(&data, options) {
+        Ok(items) => Ok(items),
+        Err(OptimizerError::InvalidInput(errors)) => {
+            for error in &errors {
+                println!(
+                    "{} {}: {}",
+                    "error".bright_red().bold(),
+                    error.field.bright_white(),
+                    error.message
+                );
+            }
+            bail!("{} has {} errors", input.display(), errors.len());
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn import_command(
+    input: PathBuf,
+    request: Option<PathBuf>,
+    output: Option<PathBuf>,
+    options: &CutListOptions,
+) -> Result<()> {
+    let items = read_cut_list(&input, options)?;
+    let count = items.len();
+    let mut request = match request {
+        Some(path) => read_request(&path)?,
+        None => OptimizationRequest::default(),
+    };
+    request.items.extend(items);
+
+    let Some(output_path) = output else {
+        println!("{}", serde_json::to_string_pretty(&request)?);
+        return Ok(());
+    };
+    let text = match output_path.extension().and_then(|s| s.to_str()) {
+        Some("yaml" | "yml") => serde_yaml::to_string(&request)?,
+        _ => serde_json::to_string_pretty(&request)?,
+    };
+    std::fs::write(&output_path, text)?;
+    println!(
+        "{} Imported {} parts into {}",
+        "✅".bright_green(),
+        count.to_string().bright_white().bold(),
+        output_path.display().to_string().bright_white()
+    );
+    Ok(())
+}
+
+fn validate_command(input: PathBuf) -> Result<()> {
+    let request = read_request(&input)?;
+    let report = optimizer_core::validate(&request);
+
+    for error in &report.errors {
+        println!(
+            "{} {}: {}",
+            "error".bright_red().bold(),
+            error.field.bright_white(),
+            error.message
+        );
+    }
+    for warning in &report.warnings {
+        println!(
+            "{} {}: {}",
+            "warning".yellow().bold(),
+            warning.field.bright_white(),
+            warning.message
+        );
+    }
+
+    if !report.is_valid() {
+        bail!(
+            "{} has {} errors and {} warnings",
+            input.display(),
+            report.errors.len(),
+            report.warnings.len()
+        );
+    }
+
+    println!(
+        "{} {} is valid ({} warnings)",
+        "✅".bright_green(),
+        input.display().to_string().bright_white(),
+        report.warnings.len()
+    );
+    Ok(())
+}
+
+fn verify_command(request: PathBuf, result: PathBuf) -> Result<()> {
+    let request = read_request(&request)?;
+    let content = std::fs::read_to_string(&result)?;
+    let plan: optimizer_core::OptimizationResult = serde_json::from_str(&content)?;
+    let violations = optimizer_core::verify(&request, &plan);
+
+    for violation in &violations {
+        println!(
+            "{} {}: {}",
+            "violation".bright_red().bold(),
+            violation.location.bright_white(),
+            violation.message
+        );
+    }
+
+    if !violations.is_empty() {
+        bail!("{} has {} violations", result.display(), violations.len());
+    }
+
+    println!(
+        "{} {} is a valid plan for its request",
+        "✅".bright_green(),
+        result.display().to_string().bright_white()
+    );
+    Ok(())
+}
+
+fn generate_command(
+    input: PathBuf,
+    output: PathBuf,
+    options: &SvgOptions,
+    per_panel: bool,
+) -> Result<()> {
     println!("{}", "🔍 Loading result...".bright_blue());
 
     // Read result file
@@ -158,10 +701,24 @@ fn generate_command(input: PathBuf, output: PathBuf) -> Result<()> {
 
     println!("{}", "🎨 Generating SVG...".bright_blue());
 
-    // Generate SVG (simple version for now)
-    let svg = generate_simple_svg(&result)?;
+    if per_panel {
+        let svgs = optimizer_core::render_panel_svgs(&result, options)?;
+        std::fs::create_dir_all(&output)?;
+        for (layout, svg) in result.layouts.iter().zip(svgs) {
+            std::fs::write(output.join(format!("{}.svg", panel_file_stem(layout))), svg)?;
+        }
+
+        println!();
+        println!(
+            "{} Saved {} SVG files to {}",
+            "✅".bright_green(),
+            result.layouts.len().to_string().bright_white().bold(),
+            output.display().to_string().bright_white()
+        );
+        return Ok(());
+    }
 
-    // Save SVG
+    let svg = optimizer_core::render_svg(&result, options)?;
     std::fs::write(&output, svg)?;
 
     println!();
@@ -174,88 +731,168 @@ fn generate_command(input: PathBuf, output: PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn generate_simple_svg(result: &optimizer_core::OptimizationResult) -> Result<String> {
-    use std::fmt::Write;
-
-    let mut svg = String::new();
-    let margin = 20.0;
-    let scale = 2.0;
-    let panel_spacing = 40.0;
-
-    let max_width = result.layouts.iter().map(|l| l.width).fold(0.0, f64::max);
-    let total_height: f64 = result
-        .layouts
-        .iter()
-        .map(|l| l.height + panel_spacing)
-        .sum();
-
-    let svg_width = (max_width / scale) + (2.0 * margin);
-    let svg_height = (total_height / scale) + (2.0 * margin);
-
-    writeln!(&mut svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
-    writeln!(
-        &mut svg,
-        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
-        svg_width, svg_height, svg_width, svg_height
-    )?;
-    writeln!(
-        &mut svg,
-        r##"  <rect width="100%" height="100%" fill="#f5f5f5"/>"##
-    )?;
-
-    let mut y_offset = margin;
-
-    for layout in &result.layouts {
-        let x = margin;
-        let panel_width = layout.width / scale;
-        let panel_height = layout.height / scale;
-
-        writeln!(
-            &mut svg,
-            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#fff" stroke="#333" stroke-width="2"/>"##,
-            x, y_offset, panel_width, panel_height
-        )?;
-
-        writeln!(
-            &mut svg,
-            r##"  <text x="{}" y="{}" font-family="Arial" font-size="14" fill="#333">{} #{}</text>"##,
-            x,
-            y_offset - 5.0,
-            layout.panel_type_id,
-            layout.panel_number
-        )?;
-
-        for placement in &layout.placements {
-            let px = x + (placement.x / scale);
-            let py = y_offset + (placement.y / scale);
-            let pw = placement.width / scale;
-            let ph = placement.height / scale;
-
-            writeln!(
-                &mut svg,
-                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#4CAF50" stroke="#2E7D32" stroke-width="1" opacity="0.7"/>"##,
-                px, py, pw, ph
-            )?;
-
-            let label = if placement.rotated {
-                format!("{} (R)", placement.item_id)
+fn pdf_command(input: PathBuf, output: PathBuf, options: &PdfOptions) -> Result<()> {
+    println!("{}", "🔍 Loading result...".bright_blue());
+
+    let content = std::fs::read_to_string(&input)?;
+    let result: optimizer_core::OptimizationResult = serde_json::from_str(&content)?;
+
+    println!("{}", "🖨️  Generating PDF...".bright_blue());
+
+    std::fs::write(&output, optimizer_core::render_pdf(&result, options))?;
+
+    println!();
+    println!(
+        "{} Saved {} panel pages to {}",
+        "✅".bright_green(),
+        result.layouts.len().to_string().bright_white().bold(),
+        output.display().to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+fn dxf_command(
+    input: PathBuf,
+    output: PathBuf,
+    options: &DxfOptions,
+    per_panel: bool,
+) -> Result<()> {
+    println!("{}", "🔍 Loading result...".bright_blue());
+
+    let content = std::fs::read_to_string(&input)?;
+    let result: optimizer_core::OptimizationResult = serde_json::from_str(&content)?;
+
+    println!("{}", "📐 Generating DXF...".bright_blue());
+
+    if per_panel {
+        let dxfs = optimizer_core::render_panel_dxfs(&result, options);
+        std::fs::create_dir_all(&output)?;
+        for (layout, dxf) in result.layouts.iter().zip(dxfs) {
+            std::fs::write(output.join(format!("{}.dxf", panel_file_stem(layout))), dxf)?;
+        }
+
+        println!();
+        println!(
+            "{} Saved {} DXF files to {}",
+            "✅".bright_green(),
+            result.layouts.len().to_string().bright_white().bold(),
+            output.display().to_string().bright_white()
+        );
+        return Ok(());
+    }
+
+    std::fs::write(&output, optimizer_core::render_dxf(&result, options))?;
+
+    println!();
+    println!(
+        "{} Saved DXF to {}",
+        "✅".bright_green(),
+        output.display().to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+/// Parses an `ID=COLOR` argument.
+fn parse_item_color(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((id, color)) if !id.is_empty() && !color.is_empty() => {
+            Ok((id.to_string(), color.to_string()))
+        }
+        _ => Err(format!("expected ID=COLOR, got '{}'", arg)),
+    }
+}
+
+/// File name of a panel's own drawing: panel type and number, or the offcut
+/// id, limited to characters that are safe in file names.
+fn panel_file_stem(layout: &optimizer_core::PanelLayout) -> String {
+    let name = match &layout.offcut_id {
+        Some(offcut_id) => offcut_id.clone(),
+        None => format!("{}-{}", layout.panel_type_id, layout.panel_number),
+    };
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
             } else {
-                placement.item_id.clone()
-            };
+                '_'
+            }
+        })
+        .collect()
+}
 
-            writeln!(
-                &mut svg,
-                r##"  <text x="{}" y="{}" font-family="Arial" font-size="10" fill="#fff" text-anchor="middle">{}</text>"##,
-                px + pw / 2.0,
-                py + ph / 2.0 + 3.0,
-                label
-            )?;
+fn remnants_command(
+    input: PathBuf,
+    output: Option<PathBuf>,
+    min_size: f64,
+    format: Option<InventoryFormat>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(&input)?;
+    let result: optimizer_core::OptimizationResult = serde_json::from_str(&content)?;
+
+    let remnants = optimizer_core::remnant_inventory(&result, min_size);
+
+    let format = format.unwrap_or_else(|| {
+        match output
+            .as_ref()
+            .and_then(|p| p.extension())
+            .and_then(|s| s.to_str())
+        {
+            Some("csv") => InventoryFormat::Csv,
+            _ => InventoryFormat::Json,
         }
+    });
+    let text = match format {
+        InventoryFormat::Json => serde_json::to_string_pretty(&remnants)?,
+        InventoryFormat::Csv => optimizer_core::remnants_to_csv(&remnants)?,
+    };
 
-        y_offset += panel_height + panel_spacing;
+    if let Some(output_path) = output {
+        std::fs::write(&output_path, text)?;
+        println!(
+            "{} Saved {} remnants to {}",
+            "✅".bright_green(),
+            remnants.len().to_string().bright_white().bold(),
+            output_path.display().to_string().bright_white()
+        );
+    } else {
+        println!("{}", text);
     }
 
-    writeln!(&mut svg, "</svg>")?;
+    Ok(())
+}
+
+fn export_command(
+    input: PathBuf,
+    output: PathBuf,
+    format: Option<TableFormat>,
+    table: ExportTable,
+) -> Result<()> {
+    println!("{}", "🔍 Loading result...".bright_blue());
+
+    let content = std::fs::read_to_string(&input)?;
+    let result: optimizer_core::OptimizationResult = serde_json::from_str(&content)?;
+
+    let format = format.unwrap_or_else(|| match output.extension().and_then(|s| s.to_str()) {
+        Some("xlsx") => TableFormat::Xlsx,
+        _ => TableFormat::Csv,
+    });
+    let data = match format {
+        TableFormat::Csv => optimizer_core::result_to_csv(&result, table)?.into_bytes(),
+        TableFormat::Xlsx => optimizer_core::result_to_xlsx(&result)?,
+    };
+    std::fs::write(&output, data)?;
+
+    let placements: usize = result.layouts.iter().map(|l| l.placements.len()).sum();
+    println!();
+    println!(
+        "{} Saved {} placements on {} panels to {}",
+        "✅".bright_green(),
+        placements.to_string().bright_white().bold(),
+        result.layouts.len().to_string().bright_white().bold(),
+        output.display().to_string().bright_white()
+    );
 
-    Ok(svg)
+    Ok(())
 }
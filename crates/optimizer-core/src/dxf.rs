@@ -0,0 +1,313 @@
+//! DXF export of cutting layouts for CNC routers and CAD software.
+//!
+//! Documents use the widely supported R12 (AC1009) format. Every panel is
+//! drawn with its lower-left corner at the origin and its parts at their
+//! exact `Placement.x/y`, on separate layers for the panel outline, trimming
+//! boundary, part outlines, part labels and unused areas.
+//!
+//! Coordinates are converted to the chosen unit before they are written.
+//! The unit is also named in `$INSUNITS`, but R12 has no such variable, so
+//! that header is only a hint for readers of newer versions.
+
+use crate::types::{DxfOptions, OptimizationResult, PanelLayout};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+const PANEL_LAYER: &str = "PANEL";
+const TRIMMING_LAYER: &str = "TRIMMING";
+const PARTS_LAYER: &str = "PARTS";
+const LABELS_LAYER: &str = "LABELS";
+const UNUSED_LAYER: &str = "UNUSED";
+
+/// Layer names with their AutoCAD color index; blocks and inserts go on "0".
+const LAYERS: [(&str, u8); 6] = [
+    ("0", 7),
+    (PANEL_LAYER, 7),
+    (TRIMMING_LAYER, 8),
+    (PARTS_LAYER, 3),
+    (LABELS_LAYER, 5),
+    (UNUSED_LAYER, 1),
+];
+
+/// Gap between panels inserted side by side by `render_dxf`, in mm.
+const PANEL_GAP: f64 = 100.0;
+
+/// Renders a result into one DXF document. Every layout becomes a block
+/// named after its panel type and number (or offcut id), with the panel's
+/// lower-left corner as base point; the blocks are inserted side by side
+/// along the x axis, in layout order.
+pub fn render_dxf(result: &OptimizationResult, options: &DxfOptions) -> String {
+    let mut dxf = String::new();
+    write_blocks_document(&mut dxf, &result.layouts, options)
+        .expect("writing to a String cannot fail");
+    dxf
+}
+
+/// Renders each layout of a result into its own DXF document, in layout
+/// order, with the panel drawn directly in model space.
+pub fn render_panel_dxfs(result: &OptimizationResult, options: &DxfOptions) -> Vec<String> {
+    result
+        .layouts
+        .iter()
+        .map(|layout| {
+            let mut dxf = String::new();
+            write_panel_document(&mut dxf, layout, options)
+                .expect("writing to a String cannot fail");
+            dxf
+        })
+        .collect()
+}
+
+fn write_blocks_document(
+    dxf: &mut String,
+    layouts: &[PanelLayout],
+    options: &DxfOptions,
+) -> std::fmt::Result {
+    let scale = options.units.per_mm();
+    let width = layouts.iter().map(|l| l.width).sum::<f64>()
+        + PANEL_GAP * layouts.len().saturating_sub(1) as f64;
+    let height = layouts.iter().map(|l| l.height).fold(0.0, f64::max);
+    write_header(dxf, options, width * scale, height * scale)?;
+
+    let names = block_names(layouts);
+    section(dxf, "BLOCKS")?;
+    for (layout, name) in layouts.iter().zip(&names) {
+        pair(dxf, 0, "BLOCK")?;
+        pair(dxf, 8, "0")?;
+        pair(dxf, 2, name)?;
+        pair(dxf, 70, 0)?;
+        point(dxf, 0.0, 0.0)?;
+        pair(dxf, 3, name)?;
+        write_panel(dxf, layout, scale)?;
+        pair(dxf, 0, "ENDBLK")?;
+        pair(dxf, 8, "0")?;
+    }
+    pair(dxf, 0, "ENDSEC")?;
+
+    section(dxf, "ENTITIES")?;
+    let mut x = 0.0;
+    for (layout, name) in layouts.iter().zip(&names) {
+        pair(dxf, 0, "INSERT")?;
+        pair(dxf, 8, "0")?;
+        pair(dxf, 2, name)?;
+        point(dxf, x * scale, 0.0)?;
+        x += layout.width + PANEL_GAP;
+    }
+    pair(dxf, 0, "ENDSEC")?;
+    pair(dxf, 0, "EOF")
+}
+
+fn write_panel_document(
+    dxf: &mut String,
+    layout: &PanelLayout,
+    options: &DxfOptions,
+) -> std::fmt::Result {
+    let scale = options.units.per_mm();
+    write_header(dxf, options, layout.width * scale, layout.height * scale)?;
+    section(dxf, "ENTITIES")?;
+    write_panel(dxf, layout, scale)?;
+    pair(dxf, 0, "ENDSEC")?;
+    pair(dxf, 0, "EOF")
+}
+
+/// Writes the header with units and extents, and the layer table.
+/// `$INSUNITS` postdates R12 and may be ignored; the extents are already
+/// in drawing units.
+fn write_header(
+    dxf: &mut String,
+    options: &DxfOptions,
+    width: f64,
+    height: f64,
+) -> std::fmt::Result {
+    section(dxf, "HEADER")?;
+    pair(dxf, 9, "$ACADVER")?;
+    pair(dxf, 1, "AC1009")?;
+    pair(dxf, 9, "$INSUNITS")?;
+    pair(dxf, 70, options.units.code())?;
+    pair(dxf, 9, "$EXTMIN")?;
+    point(dxf, 0.0, 0.0)?;
+    pair(dxf, 9, "$EXTMAX")?;
+    point(dxf, width, height)?;
+    pair(dxf, 0, "ENDSEC")?;
+
+    section(dxf, "TABLES")?;
+    pair(dxf, 0, "TABLE")?;
+    pair(dxf, 2, "LTYPE")?;
+    pair(dxf, 70, 1)?;
+    pair(dxf, 0, "LTYPE")?;
+    pair(dxf, 2, "CONTINUOUS")?;
+    pair(dxf, 70, 0)?;
+    pair(dxf, 3, "Solid line")?;
+    pair(dxf, 72, 65)?;
+    pair(dxf, 73, 0)?;
+    pair(dxf, 40, 0.0)?;
+    pair(dxf, 0, "ENDTAB")?;
+
+    pair(dxf, 0, "TABLE")?;
+    pair(dxf, 2, "LAYER")?;
+    pair(dxf, 70, LAYERS.len())?;
+    for (name, color) in LAYERS {
+        pair(dxf, 0, "LAYER")?;
+        pair(dxf, 2, name)?;
+        pair(dxf, 70, 0)?;
+        pair(dxf, 62, color)?;
+        pair(dxf, 6, "CONTINUOUS")?;
+    }
+    pair(dxf, 0, "ENDTAB")?;
+    pair(dxf, 0, "ENDSEC")
+}
+
+/// Writes the entities of one panel, with its lower-left corner at the origin.
+fn write_panel(dxf: &mut String, layout: &PanelLayout, scale: f64) -> std::fmt::Result {
+    rectangle(
+        dxf,
+        PANEL_LAYER,
+        [0.0, 0.0, layout.width, layout.height],
+        scale,
+    )?;
+    if layout.trimming > 0.0 {
+        rectangle(
+            dxf,
+            TRIMMING_LAYER,
+            [
+                layout.trimming,
+                layout.trimming,
+                layout.width - 2.0 * layout.trimming,
+                layout.height - 2.0 * layout.trimming,
+            ],
+            scale,
+        )?;
+    }
+
+    for placement in &layout.placements {
+        rectangle(
+            dxf,
+            PARTS_LAYER,
+            [placement.x, placement.y, placement.width, placement.height],
+            scale,
+        )?;
+    }
+
+    for placement in &layout.placements {
+        let label = if placement.rotated {
+            format!("{} (R)", placement.item_id)
+        } else {
+            placement.item_id.clone()
+        };
+        // Fit the label into the part, assuming characters about 0.8 times as wide as high
+        let height = (placement.height * 0.3)
+            .min(placement.width / (label.chars().count() as f64 * 0.8))
+            .min(50.0);
+        let x = (placement.x + placement.width / 2.0) * scale;
+        let y = (placement.y + placement.height / 2.0) * scale;
+
+        pair(dxf, 0, "TEXT")?;
+        pair(dxf, 8, LABELS_LAYER)?;
+        point(dxf, x, y)?;
+        pair(dxf, 40, height * scale)?;
+        pair(dxf, 1, escape(&label))?;
+        // Middle alignment: centered both ways on the alignment point
+        pair(dxf, 72, 4)?;
+        pair(dxf, 11, x)?;
+        pair(dxf, 21, y)?;
+        pair(dxf, 31, 0.0)?;
+    }
+
+    for unused in &layout.unused_areas {
+        rectangle(
+            dxf,
+            UNUSED_LAYER,
+            [unused.x, unused.y, unused.width, unused.height],
+            scale,
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a closed polyline around `[x, y, width, height]` (in mm).
+fn rectangle(dxf: &mut String, layer: &str, rect: [f64; 4], scale: f64) -> std::fmt::Result {
+    let [x, y, width, height] = rect;
+    pair(dxf, 0, "POLYLINE")?;
+    pair(dxf, 8, layer)?;
+    pair(dxf, 66, 1)?;
+    point(dxf, 0.0, 0.0)?;
+    pair(dxf, 70, 1)?;
+    for (vx, vy) in [
+        (x, y),
+        (x + width, y),
+        (x + width, y + height),
+        (x, y + height),
+    ] {
+        pair(dxf, 0, "VERTEX")?;
+        pair(dxf, 8, layer)?;
+        point(dxf, vx * scale, vy * scale)?;
+    }
+    pair(dxf, 0, "SEQEND")?;
+    pair(dxf, 8, layer)
+}
+
+fn section(dxf: &mut String, name: &str) -> std::fmt::Result {
+    pair(dxf, 0, "SECTION")?;
+    pair(dxf, 2, name)
+}
+
+fn point(dxf: &mut String, x: f64, y: f64) -> std::fmt::Result {
+    pair(dxf, 10, x)?;
+    pair(dxf, 20, y)?;
+    pair(dxf, 30, 0.0)
+}
+
+/// Writes a group code and its value, one line each.
+fn pair(dxf: &mut String, code: u16, value: impl std::fmt::Display) -> std::fmt::Result {
+    writeln!(dxf, "{:>3}", code)?;
+    writeln!(dxf, "{}", value)
+}
+
+/// Unique block names from panel type and number (or offcut id), limited to
+/// characters every DXF reader accepts.
+fn block_names(layouts: &[PanelLayout]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    layouts
+        .iter()
+        .map(|layout| {
+            let name = match &layout.offcut_id {
+                Some(offcut_id) => offcut_id.clone(),
+                None => format!("{}_{}", layout.panel_type_id, layout.panel_number),
+            };
+            let base: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+
+            let mut unique = base.clone();
+            let mut copy = 1;
+            while !taken.insert(unique.to_uppercase()) {
+                copy += 1;
+                unique = format!("{}_{}", base, copy);
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Escapes label text: non-ASCII characters as `\U+XXXX`, `%` as `%%%` so
+/// it is not read as a control code, and line breaks as spaces.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' => escaped.push_str("%%%"),
+            '\r' | '\n' => escaped.push(' '),
+            ' '..='~' => escaped.push(c),
+            _ if (c as u32) <= 0xffff => escaped.push_str(&format!("\\U+{:04X}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,261 @@
+//! Tabular export of results for ERP systems and spreadsheets: one row per
+//! placement, one row per panel and totals per panel type, as CSV or XLSX.
+
+use crate::types::{ExportTable, OptimizationResult, OptimizerError, PanelLayout, Result};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use std::collections::BTreeMap;
+
+enum Cell {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Empty,
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_string())
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<Option<&String>> for Cell {
+    fn from(text: Option<&String>) -> Self {
+        text.map_or(Cell::Empty, |t| Cell::Text(t.clone()))
+    }
+}
+
+struct Table {
+    headers: &'static [&'static str],
+    rows: Vec<Vec<Cell>>,
+}
+
+/// Writes one table of a result as CSV with a header row. Numbers are
+/// written unrounded, except utilization percentages (two decimals).
+pub fn result_to_csv(result: &OptimizationResult, table: ExportTable) -> Result<String> {
+    let table = build_table(result, table);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let export_error = |e: csv::Error| OptimizerError::Export(e.to_string());
+
+    writer.write_record(table.headers).map_err(export_error)?;
+    for row in &table.rows {
+        writer
+            .write_record(row.iter().map(|cell| match cell {
+                Cell::Text(text) => text.clone(),
+                Cell::Number(value) => value.to_string(),
+                Cell::Bool(value) => value.to_string(),
+                Cell::Empty => String::new(),
+            }))
+            .map_err(export_error)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| OptimizerError::Export(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| OptimizerError::Export(e.to_string()))
+}
+
+/// Writes all tables of a result into one XLSX workbook, with the worksheets
+/// "Placements", "Panels" and "Totals" in that order.
+pub fn result_to_xlsx(result: &OptimizationResult) -> Result<Vec<u8>> {
+    write_workbook(result).map_err(|e| OptimizerError::Export(e.to_string()))
+}
+
+fn write_workbook(result: &OptimizationResult) -> std::result::Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+
+    for (name, table) in [
+        ("Placements", ExportTable::Placements),
+        ("Panels", ExportTable::Panels),
+        ("Totals", ExportTable::Totals),
+    ] {
+        let table = build_table(result, table);
+        let sheet = workbook.add_worksheet().set_name(name)?;
+        for (column, header) in table.headers.iter().enumerate() {
+            sheet.write_string_with_format(0, column as u16, *header, &bold)?;
+        }
+        for (index, row) in table.rows.iter().enumerate() {
+            let row_number = index as u32 + 1;
+            for (column, cell) in row.iter().enumerate() {
+                let column = column as u16;
+                match cell {
+                    Cell::Text(text) => sheet.write_string(row_number, column, text)?,
+                    Cell::Number(value) => sheet.write_number(row_number, column, *value)?,
+                    Cell::Bool(value) => sheet.write_boolean(row_number, column, *value)?,
+                    Cell::Empty => sheet,
+                };
+            }
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+    }
+
+    workbook.save_to_buffer()
+}
+
+fn build_table(result: &OptimizationResult, table: ExportTable) -> Table {
+    match table {
+        ExportTable::Placements => placements_table(result),
+        ExportTable::Panels => panels_table(result),
+        ExportTable::Totals => totals_table(result),
+    }
+}
+
+/// One row per placement, in layout order.
+fn placements_table(result: &OptimizationResult) -> Table {
+    let rows = result
+        .layouts
+        .iter()
+        .flat_map(|layout| {
+            layout.placements.iter().map(move |placement| {
+                vec![
+                    layout.panel_type_id.as_str().into(),
+                    f64::from(layout.panel_number).into(),
+                    layout.offcut_id.as_ref().into(),
+                    placement.item_id.as_str().into(),
+                    placement.x.into(),
+                    placement.y.into(),
+                    placement.width.into(),
+                    placement.height.into(),
+                    Cell::Bool(placement.rotated),
+                ]
+            })
+        })
+        .collect();
+
+    Table {
+        headers: &[
+            "panel_type_id",
+            "panel_number",
+            "offcut_id",
+            "item_id",
+            "x",
+            "y",
+            "width",
+            "height",
+            "rotated",
+        ],
+        rows,
+    }
+}
+
+/// One row per layout with its utilization, in layout order.
+fn panels_table(result: &OptimizationResult) -> Table {
+    let rows = result
+        .layouts
+        .iter()
+        .map(|layout| {
+            let (used_area, total_area) = areas(layout);
+            vec![
+                layout.panel_type_id.as_str().into(),
+                f64::from(layout.panel_number).into(),
+                layout.offcut_id.as_ref().into(),
+                layout.width.into(),
+                layout.height.into(),
+                layout.trimming.into(),
+                (layout.placements.len() as f64).into(),
+                used_area.into(),
+                (total_area - used_area).into(),
+                utilization(used_area, total_area).into(),
+            ]
+        })
+        .collect();
+
+    Table {
+        headers: &[
+            "panel_type_id",
+            "panel_number",
+            "offcut_id",
+            "width",
+            "height",
+            "trimming",
+            "parts",
+            "used_area",
+            "waste_area",
+            "utilization",
+        ],
+        rows,
+    }
+}
+
+/// One row per panel type, sorted by id: the new panels from
+/// `panels_required`, the offcuts cut and the areas of all their layouts.
+fn totals_table(result: &OptimizationResult) -> Table {
+    #[derive(Default)]
+    struct Totals {
+        offcuts: u32,
+        used_area: f64,
+        total_area: f64,
+    }
+
+    let mut by_type: BTreeMap<&str, Totals> = result
+        .panels_required
+        .keys()
+        .map(|id| (id.as_str(), Totals::default()))
+        .collect();
+    for layout in &result.layouts {
+        let totals = by_type.entry(layout.panel_type_id.as_str()).or_default();
+        let (used_area, total_area) = areas(layout);
+        totals.used_area += used_area;
+        totals.total_area += total_area;
+        if layout.offcut_id.is_some() {
+            totals.offcuts += 1;
+        }
+    }
+
+    let rows = by_type
+        .into_iter()
+        .map(|(id, totals)| {
+            let panels = result.panels_required.get(id).copied().unwrap_or(0);
+            vec![
+                id.into(),
+                f64::from(panels).into(),
+                f64::from(totals.offcuts).into(),
+                totals.total_area.into(),
+                totals.used_area.into(),
+                (totals.total_area - totals.used_area).into(),
+                utilization(totals.used_area, totals.total_area).into(),
+                result
+                    .summary
+                    .cost_by_panel_type
+                    .get(id)
+                    .map_or(Cell::Empty, |&cost| cost.into()),
+            ]
+        })
+        .collect();
+
+    Table {
+        headers: &[
+            "panel_type_id",
+            "panels",
+            "offcuts",
+            "total_area",
+            "used_area",
+            "waste_area",
+            "utilization",
+            "cost",
+        ],
+        rows,
+    }
+}
+
+/// Area covered by parts and area of the whole panel.
+fn areas(layout: &PanelLayout) -> (f64, f64) {
+    let used = layout.placements.iter().map(|p| p.width * p.height).sum();
+    (used, layout.width * layout.height)
+}
+
+/// Used share of an area in percent, rounded to two decimals.
+fn utilization(used_area: f64, total_area: f64) -> f64 {
+    if total_area > 0.0 {
+        (used_area / total_area * 10_000.0).round() / 100.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,270 @@
+//! Cut-list import: reads items from CSV files and spreadsheets (XLSX, XLS,
+//! ODS) such as the part lists exported by CAD software.
+
+use crate::types::{CutListOptions, FieldError, Item, OptimizerError, Result};
+use calamine::{open_workbook_auto_from_rs, Reader};
+use std::io::Cursor;
+
+/// One table row as text, with its row number as shown in a spreadsheet.
+struct Row {
+    number: usize,
+    cells: Vec<String>,
+}
+
+/// Reads the items of a cut list. Spreadsheets are recognized by their
+/// content, anything else is read as CSV. The first non-empty row holds the
+/// column headers; empty rows are skipped.
+///
+/// Every problem is reported as a `FieldError` with a path such as
+/// `rows[4].quantity`, where 4 is the row number as shown in a spreadsheet
+/// (the header usually being row 1), or `columns.length` for a missing column.
+pub fn import_cut_list(data: &[u8], options: &CutListOptions) -> Result<Vec<Item>> {
+    let rows = if is_spreadsheet(data) {
+        read_spreadsheet(data, options)?
+    } else {
+        read_csv(data, options)?
+    };
+    parse_items(rows, options)
+}
+
+/// Zip container (XLSX, ODS) or OLE compound file (XLS).
+fn is_spreadsheet(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04") || data.starts_with(&[0xd0, 0xcf, 0x11, 0xe0])
+}
+
+fn read_spreadsheet(data: &[u8], options: &CutListOptions) -> Result<Vec<Row>> {
+    let mut workbook =
+        open_workbook_auto_from_rs(Cursor::new(data)).map_err(|e| file_error(e.to_string()))?;
+    let sheet = match &options.sheet {
+        Some(sheet) => sheet.clone(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or_else(|| file_error("The spreadsheet has no worksheets".into()))?,
+    };
+    let range = workbook
+        .worksheet_range(&sheet)
+        .map_err(|e| invalid("sheet", format!("Cannot read worksheet '{}': {}", sheet, e)))?;
+
+    // The range starts at the first used cell, not necessarily at A1
+    let (first_row, first_column) = range.start().unwrap_or((0, 0));
+    let padding = vec![String::new(); first_column as usize];
+    Ok(range
+        .rows()
+        .enumerate()
+        .map(|(index, cells)| Row {
+            number: first_row as usize + index + 1,
+            cells: padding
+                .iter()
+                .cloned()
+                .chain(cells.iter().map(ToString::to_string))
+                .collect(),
+        })
+        .collect())
+}
+
+fn read_csv(data: &[u8], options: &CutListOptions) -> Result<Vec<Row>> {
+    let delimiter = match options.delimiter {
+        Some(delimiter) if delimiter.is_ascii() => delimiter as u8,
+        Some(_) => {
+            return Err(invalid(
+                "delimiter",
+                "The delimiter must be an ASCII character".into(),
+            ))
+        }
+        None => detect_delimiter(data),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| {
+            let field = match e.position() {
+                Some(position) => format!("rows[{}]", position.line()),
+                None => "file".to_string(),
+            };
+            invalid(&field, format!("Cannot read CSV: {}", e))
+        })?;
+        rows.push(Row {
+            number: record
+                .position()
+                .map_or(rows.len() + 1, |p| p.line() as usize),
+            cells: record.iter().map(str::to_string).collect(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Picks the most frequent of `,`, `;` and tab in the first line.
+fn detect_delimiter(data: &[u8]) -> u8 {
+    let first_line = data.split(|&b| b == b'\n').next().unwrap_or_default();
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|&delimiter| {
+            (
+                first_line.iter().filter(|&&b| b == delimiter).count(),
+                // Prefer the comma on ties
+                delimiter == b',',
+            )
+        })
+        .unwrap_or(b',')
+}
+
+fn parse_items(rows: Vec<Row>, options: &CutListOptions) -> Result<Vec<Item>> {
+    let mut rows = rows
+        .into_iter()
+        .filter(|row| row.cells.iter().any(|cell| !cell.trim().is_empty()));
+    let Some(header) = rows.next() else {
+        return Err(file_error("The cut list is empty".into()));
+    };
+
+    let find = |name: &str| {
+        header
+            .cells
+            .iter()
+            .position(|cell| cell.trim().eq_ignore_ascii_case(name.trim()))
+    };
+    let mut errors = Vec::new();
+    let mut require = |key: &str, name: &str| {
+        let column = find(name);
+        if column.is_none() {
+            errors.push(FieldError {
+                field: format!("columns.{}", key),
+                message: format!("Column '{}' not found in row {}", name, header.number),
+            });
+        }
+        column
+    };
+    let length = require("length", &options.length_column);
+    let width = require("width", &options.width_column);
+    let (Some(length), Some(width)) = (length, width) else {
+        return Err(OptimizerError::InvalidInput(errors));
+    };
+    let id = find(&options.id_column);
+    let quantity = find(&options.quantity_column);
+    let rotation = find(&options.rotation_column);
+    let material = find(&options.material_column);
+    let notes = find(&options.notes_column);
+
+    let mut items = Vec::new();
+    for row in rows {
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|c| row.cells.get(c))
+                .map_or("", |cell| cell.trim())
+        };
+        let mut report = |key: &str, message: String| {
+            errors.push(FieldError {
+                field: format!("rows[{}].{}", row.number, key),
+                message: format!("Row {}: {}", row.number, message),
+            })
+        };
+
+        let mut size = |key: &str, column: usize| match parse_number(cell(Some(column))) {
+            Some(value) if value > 0.0 => Some(value),
+            _ => {
+                report(
+                    key,
+                    format!(
+                        "{} must be a positive number, got '{}'",
+                        key,
+                        cell(Some(column))
+                    ),
+                );
+                None
+            }
+        };
+        let item_width = size("length", length);
+        let item_height = size("width", width);
+
+        let copies = match cell(quantity) {
+            "" => Some(1),
+            text => match parse_number(text) {
+                Some(value) if value >= 1.0 && value.fract() == 0.0 && value <= u32::MAX as f64 => {
+                    Some(value as u32)
+                }
+                _ => {
+                    report(
+                        "quantity",
+                        format!("quantity must be a positive whole number, got '{}'", text),
+                    );
+                    None
+                }
+            },
+        };
+
+        let can_rotate = match parse_flag(cell(rotation)) {
+            Some(flag) => Some(flag),
+            None => {
+                report(
+                    "rotation",
+                    format!(
+                        "rotation must be yes/no, true/false, y/n, 1/0 or x, got '{}'",
+                        cell(rotation)
+                    ),
+                );
+                None
+            }
+        };
+
+        let (Some(item_width), Some(item_height), Some(copies), Some(can_rotate)) =
+            (item_width, item_height, copies, can_rotate)
+        else {
+            continue;
+        };
+        let text = |column: Option<usize>| Some(cell(column).to_string()).filter(|t| !t.is_empty());
+        items.push(Item {
+            id: text(id).unwrap_or_else(|| format!("row{}", row.number)),
+            width: item_width,
+            height: item_height,
+            quantity: copies,
+            can_rotate,
+            material: text(material),
+            notes: text(notes),
+            ..Default::default()
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(items)
+    } else {
+        Err(OptimizerError::InvalidInput(errors))
+    }
+}
+
+/// Parses a number, accepting a decimal comma ("12,5").
+fn parse_number(text: &str) -> Option<f64> {
+    let value = if text.contains(',') && !text.contains('.') {
+        text.replace(',', ".").parse::<f64>()
+    } else {
+        text.parse::<f64>()
+    };
+    value.ok().filter(|v| v.is_finite())
+}
+
+/// Parses a rotation flag; an empty cell allows rotation, and so does `x`,
+/// as spreadsheets often tick a column with it.
+fn parse_flag(text: &str) -> Option<bool> {
+    match text.to_ascii_lowercase().as_str() {
+        "" | "yes" | "y" | "true" | "1" | "x" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn invalid(field: &str, message: String) -> OptimizerError {
+    OptimizerError::InvalidInput(vec![FieldError {
+        field: field.to_string(),
+        message,
+    }])
+}
+
+fn file_error(message: String) -> OptimizerError {
+    invalid("file", message)
+}
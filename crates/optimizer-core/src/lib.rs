@@ -1,5 +1,19 @@
+pub mod dxf;
+pub mod export;
+pub mod import;
 pub mod optimizer;
+pub mod pdf;
+pub mod remnants;
+pub mod svg;
 pub mod types;
+pub mod verify;
 
-pub use optimizer::Optimizer;
+pub use dxf::{render_dxf, render_panel_dxfs};
+pub use export::{result_to_csv, result_to_xlsx};
+pub use import::import_cut_list;
+pub use optimizer::{validate, CancelToken, Optimizer};
+pub use pdf::render_pdf;
+pub use remnants::{remnant_inventory, remnants_to_csv};
+pub use svg::{render_panel_svgs, render_svg};
 pub use types::*;
+pub use verify::verify;
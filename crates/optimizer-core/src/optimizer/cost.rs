@@ -0,0 +1,120 @@
+use super::*;
+
+impl Optimizer {
+    /// Returns the sheet price of a panel type (zero when no cost is set).
+    pub(super) fn panel_cost(&self, panel_type: &PanelType) -> f64 {
+        panel_type.cost.unwrap_or(0.0)
+    }
+
+    /// Sums the sheet prices of every new panel in the layouts (offcuts are free).
+    pub(super) fn layouts_cost(&self, layouts: &[PanelLayout]) -> f64 {
+        self.new_panels(layouts)
+            .filter_map(|l| {
+                self.request
+                    .panel_types
+                    .iter()
+                    .find(|p| p.id == l.panel_type_id)
+            })
+            .map(|p| self.panel_cost(p))
+            .sum()
+    }
+
+    /// Orders two candidate solutions by the requested objective.
+    /// `Ordering::Less` means `a` is the better solution.
+    pub(super) fn compare_layouts(&self, a: &[PanelLayout], b: &[PanelLayout]) -> Ordering {
+        let waste_a = self.calculate_summary(a).waste_area;
+        let waste_b = self.calculate_summary(b).waste_area;
+        let by_count_then_waste = self
+            .new_panels(a)
+            .count()
+            .cmp(&self.new_panels(b).count())
+            .then_with(|| waste_a.partial_cmp(&waste_b).unwrap_or(Ordering::Equal));
+
+        match self.request.objective {
+            Objective::PanelCount => by_count_then_waste,
+            Objective::Cost => self
+                .layouts_cost(a)
+                .partial_cmp(&self.layouts_cost(b))
+                .unwrap_or(Ordering::Equal)
+                .then(by_count_then_waste),
+        }
+    }
+
+    /// Tries to move the contents of each new panel onto a single, cheaper panel type.
+    /// A panel is only replaced when every one of its items fits the cheaper sheet
+    /// and that sheet type still has stock left.
+    pub(super) fn try_downgrade_panels(
+        &self,
+        layouts: Vec<PanelLayout>,
+        expanded_items: &[Item],
+    ) -> Vec<PanelLayout> {
+        let mut current = layouts;
+
+        for idx in 0..current.len() {
+            if current[idx].offcut_id.is_some() {
+                continue;
+            }
+
+            let current_cost = self
+                .request
+                .panel_types
+                .iter()
+                .find(|p| p.id == current[idx].panel_type_id)
+                .map(|p| self.panel_cost(p))
+                .unwrap_or(0.0);
+
+            let mut cheaper: Vec<&PanelType> = self
+                .request
+                .panel_types
+                .iter()
+                .filter(|p| self.panel_cost(p) < current_cost && self.has_stock(p, &current))
+                .collect();
+            cheaper.sort_by(|a, b| {
+                self.panel_cost(a)
+                    .partial_cmp(&self.panel_cost(b))
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            let items = self.items_from_placements(&current[idx].placements, expanded_items);
+
+            for panel_type in cheaper {
+                if let Some(cheaper_layout) = self.pack_single_panel(&items, panel_type) {
+                    current[idx] = cheaper_layout;
+                    break;
+                }
+            }
+        }
+
+        self.renumber_panels(&mut current);
+        current
+    }
+
+    /// Packs all items onto one fresh panel of the given type, trying both
+    /// panel orientations. Returns `None` if any item is left over.
+    fn pack_single_panel(&self, items: &[Item], panel_type: &PanelType) -> Option<PanelLayout> {
+        'orientation: for (panel_width, panel_height) in self.panel_orientations(panel_type) {
+            let mut panel = self.pack_panel(PanelLayout {
+                panel_type_id: panel_type.id.clone(),
+                panel_number: 1,
+                width: panel_width,
+                height: panel_height,
+                trimming: panel_type.trimming,
+                placements: Vec::new(),
+                unused_areas: Vec::new(),
+                cuts: Vec::new(),
+                offcut_id: None,
+            });
+
+            for item in items {
+                match self.find_best_placement(item, &panel) {
+                    Some((placement, _)) => self.place(&mut panel, placement),
+                    None => continue 'orientation,
+                }
+            }
+
+            return Some(panel.into_layout());
+        }
+
+        None
+    }
+}
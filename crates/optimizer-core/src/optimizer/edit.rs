@@ -0,0 +1,304 @@
+use super::*;
+use crate::verify::verify;
+use std::collections::HashSet;
+
+impl Optimizer {
+    /// Applies a manual edit to a result produced for this optimizer's request.
+    ///
+    /// The edited panels are checked with `verify`: an edit that makes parts
+    /// overlap, come closer than the cut width, leave the usable panel area or
+    /// break rotation, grain or material rules fails with `InvalidEdit`, as
+    /// do adding an optional item to a panel of another panel type and
+    /// leaving a panel without edge-to-edge cuts in guillotine mode. A
+    /// reference to a missing layout, placement or item fails with
+    /// `InvalidInput`. Afterwards `unused_areas` (and `cuts` in guillotine
+    /// mode) of the edited panels, `panels_required` and the summaries are
+    /// recomputed. Parts taken off a panel are reported as missing by `verify`.
+    pub fn apply_edit(
+        &self,
+        result: &OptimizationResult,
+        edit: &LayoutEdit,
+    ) -> Result<OptimizationResult> {
+        let mut result = result.clone();
+        let touched = self.edit_layouts(&mut result.layouts, edit)?;
+
+        // Problems on panels the edit did not touch are not its fault
+        let violations: Vec<Violation> = verify(&self.request, &result)
+            .into_iter()
+            .filter(|v| {
+                touched
+                    .iter()
+                    .any(|i| v.location.starts_with(&format!("layouts[{}].", i)))
+            })
+            .collect();
+        if !violations.is_empty() {
+            return Err(OptimizerError::InvalidEdit(violations));
+        }
+
+        let expanded_items = self.expand_items();
+        let mut violations = Vec::new();
+        for &i in &touched {
+            let layout = &mut result.layouts[i];
+            self.finalize_placements(std::slice::from_mut(layout), &expanded_items);
+            layout.unused_areas = self.compute_output_unused_areas(layout);
+            if self.request.guillotine {
+                match self.compute_guillotine_cuts(layout) {
+                    Some(cuts) => layout.cuts = cuts,
+                    None => violations.push(Violation {
+                        kind: ViolationKind::NotGuillotine,
+                        location: format!("layouts[{}]", i),
+                        message: format!(
+                            "Panel {} can no longer be cut with edge-to-edge cuts",
+                            layout.panel_number
+                        ),
+                    }),
+                }
+            }
+        }
+        if !violations.is_empty() {
+            return Err(OptimizerError::InvalidEdit(violations));
+        }
+
+        result.layouts.retain(|l| !l.placements.is_empty());
+        self.renumber_panels(&mut result.layouts);
+        self.recompute_totals(&mut result);
+        Ok(result)
+    }
+
+    /// Changes the placements as the edit asks and returns the indices of the
+    /// layouts it changed.
+    fn edit_layouts(&self, layouts: &mut [PanelLayout], edit: &LayoutEdit) -> Result<Vec<usize>> {
+        match edit {
+            LayoutEdit::Move { target, x, y } => {
+                check_placement(layouts, target, "target")?;
+                let placement = &mut layouts[target.layout].placements[target.placement];
+                placement.x = *x;
+                placement.y = *y;
+                Ok(vec![target.layout])
+            }
+            LayoutEdit::Rotate { target } => {
+                check_placement(layouts, target, "target")?;
+                let placement = &mut layouts[target.layout].placements[target.placement];
+                std::mem::swap(&mut placement.width, &mut placement.height);
+                placement.rotated = !placement.rotated;
+                Ok(vec![target.layout])
+            }
+            LayoutEdit::Swap { first, second } => {
+                check_placement(layouts, first, "first")?;
+                check_placement(layouts, second, "second")?;
+                let mut a = layouts[first.layout].placements[first.placement].clone();
+                let mut b = layouts[second.layout].placements[second.placement].clone();
+                std::mem::swap(&mut a.x, &mut b.x);
+                std::mem::swap(&mut a.y, &mut b.y);
+
+                // Each part takes the other's slot, so parts on different panels trade panels
+                layouts[first.layout].placements[first.placement] = b;
+                layouts[second.layout].placements[second.placement] = a;
+                Ok(touched(first.layout, second.layout))
+            }
+            LayoutEdit::MoveToPanel {
+                target,
+                layout,
+                x,
+                y,
+            } => {
+                check_placement(layouts, target, "target")?;
+                check_layout(layouts, *layout, "layout")?;
+                let mut placement = layouts[target.layout].placements.remove(target.placement);
+                placement.x = *x;
+                placement.y = *y;
+                layouts[*layout].placements.push(placement);
+                Ok(touched(target.layout, *layout))
+            }
+            LayoutEdit::Remove { target } => {
+                check_placement(layouts, target, "target")?;
+                layouts[target.layout].placements.remove(target.placement);
+                Ok(vec![target.layout])
+            }
+            LayoutEdit::Add {
+                layout,
+                item_id,
+                x,
+                y,
+                rotated,
+            } => {
+                check_layout(layouts, *layout, "layout")?;
+                let (item, owner) = self.placeable_item(item_id)?;
+                match owner {
+                    // Optional items only fill panels of their own panel type
+                    Some(owner) if owner != layouts[*layout].panel_type_id => {
+                        return Err(OptimizerError::InvalidEdit(vec![Violation {
+                            kind: ViolationKind::WrongPanelType,
+                            location: format!("layouts[{}]", layout),
+                            message: format!(
+                                "Optional item '{}' belongs to panel type '{}', not '{}'",
+                                item_id, owner, layouts[*layout].panel_type_id
+                            ),
+                        }]));
+                    }
+                    Some(_) => {}
+                    None => {
+                        let placed = layouts
+                            .iter()
+                            .flat_map(|l| &l.placements)
+                            .any(|p| p.item_id == *item_id);
+                        if placed {
+                            return Err(invalid_reference(
+                                "item_id",
+                                format!("Item '{}' is already placed", item_id),
+                            ));
+                        }
+                    }
+                }
+
+                let (width, height) = if *rotated {
+                    (item.height, item.width)
+                } else {
+                    (item.width, item.height)
+                };
+                layouts[*layout].placements.push(Placement {
+                    item_id: item_id.clone(),
+                    x: *x,
+                    y: *y,
+                    width,
+                    height,
+                    rotated: *rotated,
+                    ..Default::default()
+                });
+                Ok(vec![*layout])
+            }
+        }
+    }
+
+    /// Returns the item copy (at cut size) or optional item with this id,
+    /// and for an optional item the id of the panel type it belongs to.
+    fn placeable_item(&self, item_id: &str) -> Result<(Item, Option<String>)> {
+        if let Some(item) = self.expand_items().into_iter().find(|i| i.id == item_id) {
+            return Ok((item, None));
+        }
+
+        self.request
+            .panel_types
+            .iter()
+            .flat_map(|p| p.optional_items.iter().map(move |o| (p, o)))
+            .find(|(_, o)| o.id == item_id)
+            .map(|(panel_type, optional)| {
+                let item = Item {
+                    id: optional.id.clone(),
+                    width: optional.width,
+                    height: optional.height,
+                    quantity: 1,
+                    can_rotate: optional.can_rotate,
+                    grain: optional.grain,
+                    ..Default::default()
+                };
+                (item, Some(panel_type.id.clone()))
+            })
+            .ok_or_else(|| invalid_reference("item_id", format!("Unknown item '{}'", item_id)))
+    }
+
+    /// Recomputes everything derived from the layouts: panel counts,
+    /// summaries (keeping a proven lower bound) and the optional items and
+    /// offcuts used.
+    fn recompute_totals(&self, result: &mut OptimizationResult) {
+        result.panels_required = self.count_panels(&result.layouts);
+        result.summary = with_lower_bound(
+            self.calculate_summary(&result.layouts),
+            result.summary.lower_bound,
+        );
+
+        for material in &mut result.materials {
+            let group = Optimizer {
+                request: self.group_request(&material.material),
+            };
+            let layouts: Vec<PanelLayout> = result
+                .layouts
+                .iter()
+                .filter(|l| {
+                    group
+                        .request
+                        .panel_types
+                        .iter()
+                        .any(|p| p.id == l.panel_type_id)
+                })
+                .cloned()
+                .collect();
+            material.panels_required = group.count_panels(&layouts);
+            material.summary = with_lower_bound(
+                group.calculate_summary(&layouts),
+                material.summary.lower_bound,
+            );
+        }
+
+        let optional_ids: HashSet<&str> = self
+            .request
+            .panel_types
+            .iter()
+            .flat_map(|p| &p.optional_items)
+            .map(|o| o.id.as_str())
+            .collect();
+        result.optional_items_used = result
+            .layouts
+            .iter()
+            .flat_map(|l| &l.placements)
+            .filter(|p| optional_ids.contains(p.item_id.as_str()))
+            .map(|p| p.item_id.clone())
+            .collect();
+        result.offcuts_used = result
+            .layouts
+            .iter()
+            .filter_map(|l| l.offcut_id.clone())
+            .collect();
+    }
+}
+
+/// Layout indices changed by an edit between two layouts.
+fn touched(first: usize, second: usize) -> Vec<usize> {
+    if first == second {
+        vec![first]
+    } else {
+        vec![first, second]
+    }
+}
+
+/// `InvalidInput` for a field of the edit, such as `edit.target.layout`.
+fn invalid_reference(field: &str, message: String) -> OptimizerError {
+    OptimizerError::InvalidInput(vec![FieldError {
+        field: format!("edit.{}", field),
+        message,
+    }])
+}
+
+fn check_layout(layouts: &[PanelLayout], index: usize, field: &str) -> Result<()> {
+    if index >= layouts.len() {
+        return Err(invalid_reference(
+            field,
+            format!("Layout {} does not exist", index),
+        ));
+    }
+    Ok(())
+}
+
+fn check_placement(layouts: &[PanelLayout], target: &PlacementRef, field: &str) -> Result<()> {
+    check_layout(layouts, target.layout, &format!("{}.layout", field))?;
+    if target.placement >= layouts[target.layout].placements.len() {
+        return Err(invalid_reference(
+            &format!("{}.placement", field),
+            format!(
+                "Layout {} has no placement {}",
+                target.layout, target.placement
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Carries a proven lower bound on the panel count over to a recomputed summary.
+fn with_lower_bound(mut summary: Summary, lower_bound: Option<u32>) -> Summary {
+    if let Some(lower_bound) = lower_bound {
+        summary.lower_bound = Some(lower_bound);
+        summary.optimality_proven = Some(summary.total_panels <= lower_bound);
+        summary.optimality_gap = Some(summary.total_panels.saturating_sub(lower_bound));
+    }
+    summary
+}
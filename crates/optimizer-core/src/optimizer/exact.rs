@@ -0,0 +1,305 @@
+use super::*;
+
+/// Largest expanded item count the exact search is run for.
+pub(super) const EXACT_MAX_ITEMS: usize = 12;
+
+/// Search nodes after which the exact search gives up without a proof.
+const EXACT_NODE_LIMIT: u64 = 200_000;
+
+/// Outcome of the exact search on the number of new panels.
+pub(super) struct ExactReport {
+    pub lower_bound: u32,
+    pub proven: bool,
+}
+
+/// Result of one depth-first search for a packing with a panel limit.
+enum SearchOutcome {
+    Found,
+    Exhausted,
+    Aborted,
+}
+
+impl Optimizer {
+    /// Tries to prove the panel count of a solution optimal, replacing the
+    /// solution when a packing with fewer new panels is found.
+    ///
+    /// Starting at the area lower bound, a branch-and-bound search looks for a
+    /// packing with each smaller panel count in turn. It places the items
+    /// largest first, branching over every candidate position of the next item
+    /// from `generate_candidate_placements` on the open panels, a new panel of
+    /// each type, or an unused offcut, and prunes branches whose remaining item
+    /// area exceeds the remaining free area. Every panel count the search rules
+    /// out raises the lower bound. Proofs hold within the optimizer's placement
+    /// model; in guillotine mode, or when the search runs past its node limit,
+    /// is cancelled or the instance has more than `EXACT_MAX_ITEMS` items, only
+    /// a matching area bound proves optimality.
+    pub(super) fn exact_search(
+        &self,
+        expanded_items: &[Item],
+        layouts: Vec<PanelLayout>,
+        monitor: &Monitor,
+    ) -> (Vec<PanelLayout>, ExactReport) {
+        let mut lower_bound = self.panel_lower_bound(expanded_items);
+        let mut layouts = layouts;
+        let panel_count = self.new_panels(&layouts).count() as u32;
+
+        if panel_count <= lower_bound || expanded_items.len() > EXACT_MAX_ITEMS {
+            let proven = panel_count <= lower_bound;
+            return (
+                layouts,
+                ExactReport {
+                    lower_bound,
+                    proven,
+                },
+            );
+        }
+
+        // Without guillotine constraints, ruling out a panel count is a proof
+        let complete = !self.request.guillotine;
+        let mut items: Vec<Item> = expanded_items.to_vec();
+        items.sort_by(|a, b| {
+            (b.width * b.height)
+                .partial_cmp(&(a.width * a.height))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut nodes = 0;
+
+        for max_panels in lower_bound..panel_count {
+            monitor.report(Phase::Exact { max_panels });
+            let mut partial = Vec::new();
+            let outcome = self.exact_dfs(
+                &mut items,
+                &mut partial,
+                max_panels as usize,
+                &mut nodes,
+                monitor,
+            );
+            match outcome {
+                SearchOutcome::Found => {
+                    let mut partial = self.unpack_panels(partial);
+                    self.renumber_panels(&mut partial);
+                    if self.compare_layouts(&partial, &layouts) == Ordering::Less
+                        && self.is_cuttable(&partial)
+                    {
+                        self.record_solution(monitor, &partial, expanded_items);
+                        layouts = partial;
+                    }
+                    break;
+                }
+                SearchOutcome::Exhausted if complete => lower_bound = max_panels + 1,
+                SearchOutcome::Exhausted | SearchOutcome::Aborted => break,
+            }
+        }
+
+        // The bound only rises when smaller panel counts are ruled out
+        let proven = self.new_panels(&layouts).count() as u32 <= lower_bound;
+
+        (
+            layouts,
+            ExactReport {
+                lower_bound,
+                proven,
+            },
+        )
+    }
+
+    /// Area bound on the number of new panels: the item area that offcuts
+    /// cannot absorb, divided by the largest usable panel area.
+    pub(super) fn panel_lower_bound(&self, expanded_items: &[Item]) -> u32 {
+        let item_area: f64 = expanded_items.iter().map(|i| i.width * i.height).sum();
+        let offcut_area: f64 = self
+            .request
+            .offcuts
+            .iter()
+            .map(|o| o.width * o.height)
+            .sum();
+        let max_panel_area = self
+            .request
+            .panel_types
+            .iter()
+            .filter(|p| p.available != Some(0))
+            .map(|p| (p.width - p.trimming * 2.0) * (p.height - p.trimming * 2.0))
+            .fold(0.0, f64::max);
+
+        if max_panel_area <= 0.0 {
+            return 0;
+        }
+
+        let remaining = (item_area - offcut_area).max(0.0);
+        (remaining / max_panel_area - 1e-9).ceil().max(0.0) as u32
+    }
+
+    /// Depth-first search for a packing of all remaining items that opens at
+    /// most `max_panels` new panels.
+    fn exact_dfs(
+        &self,
+        remaining: &mut Vec<Item>,
+        layouts: &mut Vec<PackedPanel>,
+        max_panels: usize,
+        nodes: &mut u64,
+        monitor: &Monitor,
+    ) -> SearchOutcome {
+        if remaining.is_empty() {
+            return SearchOutcome::Found;
+        }
+
+        *nodes += 1;
+        if *nodes > EXACT_NODE_LIMIT || monitor.is_cancelled() {
+            return SearchOutcome::Aborted;
+        }
+
+        let new_panels = self.new_panels(layouts).count();
+        if self.remaining_capacity(layouts, max_panels - new_panels)
+            < remaining.iter().map(|i| i.width * i.height).sum::<f64>() - 1e-6
+        {
+            return SearchOutcome::Exhausted;
+        }
+
+        // Items are sorted largest first and placed in that order: branching
+        // on any other remaining item would only revisit the same packings
+        let item = remaining.remove(0);
+        let mut aborted = false;
+
+        let mut targets: Vec<Option<PackedPanel>> = vec![None; layouts.len()];
+        targets.extend(
+            self.empty_panel_candidates(layouts, new_panels < max_panels)
+                .into_iter()
+                .map(|empty| Some(self.pack_panel(empty))),
+        );
+
+        for (layout_idx, target) in targets.into_iter().enumerate() {
+            let opened = target.is_some();
+            if let Some(empty) = target {
+                layouts.push(empty);
+            }
+            let slot = if opened {
+                layouts.len() - 1
+            } else {
+                layout_idx
+            };
+
+            for (placement, _, _, _) in self.generate_candidate_placements(&item, &layouts[slot]) {
+                self.place(&mut layouts[slot], placement);
+                let outcome = self.exact_dfs(remaining, layouts, max_panels, nodes, monitor);
+                match outcome {
+                    SearchOutcome::Found => {
+                        // Leave the solution in place; restore only the item list
+                        remaining.insert(0, item);
+                        return SearchOutcome::Found;
+                    }
+                    SearchOutcome::Aborted => aborted = true,
+                    SearchOutcome::Exhausted => {}
+                }
+                let last = layouts[slot].layout().placements.len() - 1;
+                self.unplace(&mut layouts[slot], last);
+
+                if aborted {
+                    break;
+                }
+            }
+
+            if opened {
+                layouts.pop();
+            }
+            if aborted {
+                break;
+            }
+        }
+
+        remaining.insert(0, item);
+        if aborted {
+            return SearchOutcome::Aborted;
+        }
+
+        SearchOutcome::Exhausted
+    }
+
+    /// Empty layouts the next item may open: one per panel type and orientation
+    /// (if another new panel is allowed and in stock) and one per unused offcut.
+    fn empty_panel_candidates(
+        &self,
+        layouts: &[PackedPanel],
+        allow_new_panel: bool,
+    ) -> Vec<PanelLayout> {
+        let mut candidates = Vec::new();
+
+        if allow_new_panel {
+            for panel_type in &self.request.panel_types {
+                if !self.has_stock(panel_type, layouts) {
+                    continue;
+                }
+
+                for (width, height) in self.panel_orientations(panel_type) {
+                    candidates.push(PanelLayout {
+                        panel_type_id: panel_type.id.clone(),
+                        panel_number: 1,
+                        width,
+                        height,
+                        trimming: panel_type.trimming,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        for offcut in &self.request.offcuts {
+            if layouts
+                .iter()
+                .any(|l| l.layout().offcut_id.as_ref() == Some(&offcut.id))
+            {
+                continue;
+            }
+            let Some(panel_type) = self.offcut_panel_type(offcut) else {
+                continue;
+            };
+
+            for (width, height) in self.panel_orientations(&panel_type) {
+                candidates.push(PanelLayout {
+                    panel_type_id: panel_type.id.clone(),
+                    panel_number: 1,
+                    width,
+                    height,
+                    offcut_id: Some(offcut.id.clone()),
+                    ..Default::default()
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Upper bound on the item area that still fits: free area of the open
+    /// layouts, the unused offcuts, and `extra_panels` of the largest panel type.
+    fn remaining_capacity(&self, layouts: &[PackedPanel], extra_panels: usize) -> f64 {
+        let open: f64 = layouts
+            .iter()
+            .map(PackedPanel::layout)
+            .map(|l| {
+                let usable = (l.width - l.trimming * 2.0) * (l.height - l.trimming * 2.0);
+                let used: f64 = l.placements.iter().map(|p| p.width * p.height).sum();
+                usable - used
+            })
+            .sum();
+        let offcuts: f64 = self
+            .request
+            .offcuts
+            .iter()
+            .filter(|o| {
+                !layouts
+                    .iter()
+                    .any(|l| l.layout().offcut_id.as_ref() == Some(&o.id))
+            })
+            .map(|o| o.width * o.height)
+            .sum();
+        let max_panel_area = self
+            .request
+            .panel_types
+            .iter()
+            .filter(|p| self.has_stock(p, layouts))
+            .map(|p| (p.width - p.trimming * 2.0) * (p.height - p.trimming * 2.0))
+            .fold(0.0, f64::max);
+
+        open + offcuts + max_panel_area * extra_panels as f64
+    }
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+impl Optimizer {
+    /// Returns the grain direction of the panel type a layout was cut from.
+    pub(super) fn layout_grain(&self, layout: &PanelLayout) -> Grain {
+        self.request
+            .panel_types
+            .iter()
+            .find(|p| p.id == layout.panel_type_id)
+            .map(|p| p.grain)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the item may be placed in the given orientation on a panel
+    /// with the given grain (expressed in layout coordinates).
+    pub(super) fn orientation_allowed(
+        &self,
+        item: &Item,
+        panel_grain: Grain,
+        rotated: bool,
+    ) -> bool {
+        if rotated && !item.can_rotate {
+            return false;
+        }
+
+        if item.grain.is_none() || panel_grain.is_none() {
+            return true;
+        }
+
+        let placed_grain = if rotated {
+            item.grain.rotated()
+        } else {
+            item.grain
+        };
+        placed_grain == panel_grain
+    }
+}
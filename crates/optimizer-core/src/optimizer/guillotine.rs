@@ -0,0 +1,283 @@
+use super::layout::UnusedArea;
+use super::*;
+
+/// Tolerance in mm when matching placements against free rectangles and cut lines.
+const EPS: f64 = 0.5;
+
+impl Optimizer {
+    /// Splits the free rectangle that holds a placement into two disjoint
+    /// rectangles using a single edge-to-edge cut (guillotine split).
+    /// The split direction is chosen so the larger leftover rectangle is as big
+    /// as possible. The packer only places parts in the corner of a free
+    /// rectangle; a placement anywhere else (as in a layout edited by hand)
+    /// removes every free rectangle it overlaps, so the pieces stay disjoint
+    /// and nothing is placed across a cut that cannot be made.
+    pub(super) fn split_free_rects_guillotine(
+        &self,
+        mut free_rects: Vec<UnusedArea>,
+        placed: &UnusedArea,
+    ) -> Vec<UnusedArea> {
+        let kerf = self.request.cut_width;
+        let holder = free_rects.iter().position(|r| {
+            (placed.x - r.x).abs() < EPS
+                && (placed.y - r.y).abs() < EPS
+                && placed.x + placed.width - kerf <= r.x + r.width + EPS
+                && placed.y + placed.height - kerf <= r.y + r.height + EPS
+        });
+
+        let Some(idx) = holder else {
+            free_rects.retain(|r| {
+                r.x >= placed.x + placed.width
+                    || r.x + r.width <= placed.x
+                    || r.y >= placed.y + placed.height
+                    || r.y + r.height <= placed.y
+            });
+            return free_rects;
+        };
+
+        let rect = free_rects.remove(idx);
+        let rect_right = rect.x + rect.width;
+        let rect_top = rect.y + rect.height;
+        let placed_right = (placed.x + placed.width).min(rect_right);
+        let placed_top = (placed.y + placed.height).min(rect_top);
+        // Part edges without the kerf: the first cut's kerf is not usable by the short piece
+        let part_right = (placed.x + placed.width - kerf).min(rect_right);
+        let part_top = (placed.y + placed.height - kerf).min(rect_top);
+
+        // Horizontal cut first: full-width piece above, short piece to the right
+        let horizontal = [
+            UnusedArea {
+                x: rect.x,
+                y: placed_top,
+                width: rect.width,
+                height: rect_top - placed_top,
+            },
+            UnusedArea {
+                x: placed_right,
+                y: rect.y,
+                width: rect_right - placed_right,
+                height: part_top - rect.y,
+            },
+        ];
+
+        // Vertical cut first: full-height piece to the right, short piece above
+        let vertical = [
+            UnusedArea {
+                x: placed_right,
+                y: rect.y,
+                width: rect_right - placed_right,
+                height: rect.height,
+            },
+            UnusedArea {
+                x: rect.x,
+                y: placed_top,
+                width: part_right - rect.x,
+                height: rect_top - placed_top,
+            },
+        ];
+
+        let largest =
+            |rects: &[UnusedArea; 2]| rects.iter().map(|r| r.width * r.height).fold(0.0, f64::max);
+
+        let pieces = if largest(&horizontal) >= largest(&vertical) {
+            horizontal
+        } else {
+            vertical
+        };
+
+        free_rects.extend(
+            pieces
+                .into_iter()
+                .filter(|r| r.width > 0.0 && r.height > 0.0),
+        );
+        free_rects
+    }
+
+    /// Returns true unless guillotine mode is on and some layout cannot be cut
+    /// with edge-to-edge cuts. Candidate solutions failing this are rejected.
+    pub(super) fn is_cuttable(&self, layouts: &[PanelLayout]) -> bool {
+        !self.request.guillotine
+            || layouts
+                .iter()
+                .all(|layout| self.compute_guillotine_cuts(layout).is_some())
+    }
+
+    /// Derives the guillotine cut sequence for a layout by recursively splitting
+    /// the usable area with edge-to-edge cuts that do not cross any part.
+    /// Returns `None` if some parts cannot be separated by guillotine cuts.
+    pub(super) fn compute_guillotine_cuts(&self, layout: &PanelLayout) -> Option<Vec<Cut>> {
+        let region = UnusedArea {
+            x: layout.trimming,
+            y: layout.trimming,
+            width: layout.width - (layout.trimming * 2.0),
+            height: layout.height - (layout.trimming * 2.0),
+        };
+
+        // First-stage cuts run along the longer side of the panel
+        let first = if layout.width >= layout.height {
+            CutDirection::Horizontal
+        } else {
+            CutDirection::Vertical
+        };
+
+        let placements: Vec<&Placement> = layout.placements.iter().collect();
+        let mut cuts = Vec::new();
+        if self.cut_region(&region, &placements, 1, first, &mut cuts) {
+            Some(cuts)
+        } else {
+            None
+        }
+    }
+
+    /// Cuts one region and recurses into the resulting pieces.
+    /// Returns false when the placements in the region cannot be separated.
+    fn cut_region(
+        &self,
+        region: &UnusedArea,
+        placements: &[&Placement],
+        stage: u32,
+        preferred: CutDirection,
+        cuts: &mut Vec<Cut>,
+    ) -> bool {
+        if placements.is_empty() {
+            return true;
+        }
+
+        if let [only] = placements {
+            let fills_region = (only.x - region.x).abs() < EPS
+                && (only.y - region.y).abs() < EPS
+                && (only.width - region.width).abs() < EPS
+                && (only.height - region.height).abs() < EPS;
+            if fills_region {
+                return true;
+            }
+        }
+
+        let other = match preferred {
+            CutDirection::Vertical => CutDirection::Horizontal,
+            CutDirection::Horizontal => CutDirection::Vertical,
+        };
+
+        for direction in [preferred, other] {
+            let positions = self.cut_positions(region, placements, direction);
+            if positions.is_empty() {
+                continue;
+            }
+
+            let kerf = self.request.cut_width;
+            let (start, end) = match direction {
+                CutDirection::Vertical => (region.y, region.y + region.height),
+                CutDirection::Horizontal => (region.x, region.x + region.width),
+            };
+
+            for &position in &positions {
+                cuts.push(Cut {
+                    stage,
+                    direction,
+                    position,
+                    start,
+                    end,
+                });
+            }
+
+            // Pieces between consecutive cuts (each cut consumes the kerf)
+            let (region_start, region_end) = match direction {
+                CutDirection::Vertical => (region.x, region.x + region.width),
+                CutDirection::Horizontal => (region.y, region.y + region.height),
+            };
+            let mut bounds = vec![region_start];
+            for &position in &positions {
+                bounds.push(position);
+                bounds.push((position + kerf).min(region_end));
+            }
+            bounds.push(region_end);
+
+            for pair in bounds.chunks(2) {
+                let (lo, hi) = (pair[0], pair[1]);
+                if hi - lo <= 0.0 {
+                    continue;
+                }
+
+                let piece = match direction {
+                    CutDirection::Vertical => UnusedArea {
+                        x: lo,
+                        y: region.y,
+                        width: hi - lo,
+                        height: region.height,
+                    },
+                    CutDirection::Horizontal => UnusedArea {
+                        x: region.x,
+                        y: lo,
+                        width: region.width,
+                        height: hi - lo,
+                    },
+                };
+
+                let inside: Vec<&Placement> = placements
+                    .iter()
+                    .copied()
+                    .filter(|p| match direction {
+                        CutDirection::Vertical => p.x >= lo - EPS && p.x + p.width <= hi + EPS,
+                        CutDirection::Horizontal => p.y >= lo - EPS && p.y + p.height <= hi + EPS,
+                    })
+                    .collect();
+
+                if !self.cut_region(&piece, &inside, stage + 1, other, cuts) {
+                    return false;
+                }
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns every position (sorted) where an edge-to-edge cut in the given
+    /// direction fits between parts of the region without touching any of them.
+    fn cut_positions(
+        &self,
+        region: &UnusedArea,
+        placements: &[&Placement],
+        direction: CutDirection,
+    ) -> Vec<f64> {
+        let kerf = self.request.cut_width;
+        let span = |p: &Placement| match direction {
+            CutDirection::Vertical => (p.x, p.x + p.width),
+            CutDirection::Horizontal => (p.y, p.y + p.height),
+        };
+        let (region_start, region_end) = match direction {
+            CutDirection::Vertical => (region.x, region.x + region.width),
+            CutDirection::Horizontal => (region.y, region.y + region.height),
+        };
+
+        // Cuts can only start right after a part or end right before one
+        let mut candidates: Vec<f64> = placements
+            .iter()
+            .flat_map(|p| {
+                let (lo, hi) = span(p);
+                [hi, lo - kerf]
+            })
+            .filter(|&pos| pos > region_start + EPS && pos < region_end - EPS)
+            .collect();
+        candidates.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        candidates.dedup_by(|a, b| (*a - *b).abs() < EPS);
+
+        let mut positions: Vec<f64> = Vec::new();
+        for pos in candidates {
+            let crosses_part = placements.iter().any(|p| {
+                let (lo, hi) = span(p);
+                lo < pos + kerf - EPS && hi > pos + EPS
+            });
+            // Keep cuts whose kerf does not overlap the previous cut's kerf
+            let overlaps_previous = positions
+                .last()
+                .is_some_and(|&last| pos < last + kerf - EPS);
+            if !crosses_part && !overlaps_previous {
+                positions.push(pos);
+            }
+        }
+
+        positions
+    }
+}
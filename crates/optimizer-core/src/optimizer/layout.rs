@@ -33,7 +33,19 @@ impl Optimizer {
     /// Returns every rectangular area that is still free on the panel.
     /// Uses maxrects algorithm: maintains a set of maximal free rectangles.
     /// This produces better packing by tracking all possible placement positions.
+    /// In guillotine mode the free rectangles are disjoint pieces produced by
+    /// edge-to-edge cuts instead.
     pub(super) fn find_unused_areas(&self, layout: &PanelLayout) -> Vec<UnusedArea> {
+        // Remove rectangles that are too small to be useful
+        self.replay_free_rects(layout)
+            .into_iter()
+            .filter(|r| r.width > 0.5 && r.height > 0.5)
+            .collect()
+    }
+
+    /// Replays the placements of a layout on its empty usable area and returns
+    /// the resulting free rectangles, tiny ones included.
+    pub(super) fn replay_free_rects(&self, layout: &PanelLayout) -> Vec<UnusedArea> {
         let usable_width = layout.width - (layout.trimming * 2.0);
         let usable_height = layout.height - (layout.trimming * 2.0);
 
@@ -51,27 +63,36 @@ impl Optimizer {
 
         // For each placed item, split any overlapping free rectangles
         for placement in &layout.placements {
-            let placed_rect = UnusedArea {
-                x: placement.x,
-                y: placement.y,
-                width: placement.width + self.request.cut_width,
-                height: placement.height + self.request.cut_width,
-            };
-
-            free_rects = self.split_free_rects_around_placement(free_rects, &placed_rect);
+            free_rects = self.split_free_rects(free_rects, placement);
         }
 
-        // Remove rectangles that are too small to be useful
         free_rects
-            .into_iter()
-            .filter(|r| r.width > 0.5 && r.height > 0.5)
-            .collect()
+    }
+
+    /// Splits free rectangles around one placement (plus its kerf).
+    pub(super) fn split_free_rects(
+        &self,
+        free_rects: Vec<UnusedArea>,
+        placement: &Placement,
+    ) -> Vec<UnusedArea> {
+        let placed_rect = UnusedArea {
+            x: placement.x,
+            y: placement.y,
+            width: placement.width + self.request.cut_width,
+            height: placement.height + self.request.cut_width,
+        };
+
+        if self.request.guillotine {
+            self.split_free_rects_guillotine(free_rects, &placed_rect)
+        } else {
+            self.split_free_rects_around_placement(free_rects, &placed_rect)
+        }
     }
 
     /// Splits free rectangles around a placed item using maxrects algorithm.
     /// This creates up to 4 new rectangles for each overlapping free rect,
     /// then removes any rectangles that are fully contained by others.
-    fn split_free_rects_around_placement(
+    pub(super) fn split_free_rects_around_placement(
         &self,
         free_rects: Vec<UnusedArea>,
         placed: &UnusedArea,
@@ -84,13 +105,17 @@ impl Optimizer {
                 continue;
             }
 
-            // Generate up to 4 maximal rectangles from the remaining space
+            // Generate up to 4 maximal rectangles from the remaining space.
+            // The placed rect carries its kerf on the right and top only, so
+            // the left and bottom pieces leave room for the cut before it.
+            let kerf = self.request.cut_width;
+
             // Left piece: from rect left edge to placed left edge
-            if placed.x > rect.x {
+            if placed.x - kerf > rect.x {
                 new_free_rects.push(UnusedArea {
                     x: rect.x,
                     y: rect.y,
-                    width: placed.x - rect.x,
+                    width: placed.x - kerf - rect.x,
                     height: rect.height,
                 });
             }
@@ -108,12 +133,12 @@ impl Optimizer {
             }
 
             // Bottom piece: from rect bottom edge to placed bottom edge
-            if placed.y > rect.y {
+            if placed.y - kerf > rect.y {
                 new_free_rects.push(UnusedArea {
                     x: rect.x,
                     y: rect.y,
                     width: rect.width,
-                    height: placed.y - rect.y,
+                    height: placed.y - kerf - rect.y,
                 });
             }
 
@@ -0,0 +1,117 @@
+use super::*;
+
+impl Optimizer {
+    /// Returns true if any item or panel type names a material.
+    pub(super) fn uses_materials(&self) -> bool {
+        self.request.items.iter().any(|i| i.material.is_some())
+            || self
+                .request
+                .panel_types
+                .iter()
+                .any(|p| p.material.is_some())
+    }
+
+    /// Packs every material group separately and merges the results.
+    /// The combined `panels_required` and summary cover all groups, while
+    /// `materials` keeps the figures of each group. A search time limit is
+    /// shared evenly between the groups.
+    pub(super) fn optimize_materials(&self, monitor: &Monitor) -> Result<OptimizationResult> {
+        let mut layouts = Vec::new();
+        let mut optional_items_used = Vec::new();
+        let mut offcuts_used = Vec::new();
+        let mut materials = Vec::new();
+
+        let groups = self.material_groups();
+        let group_count = groups.len() as u64;
+
+        // Groups are independent; the first failing group (in order) is reported
+        let outcomes = parallel::map_ordered(&groups, |material| {
+            let mut request = self.group_request(material);
+            if let Some(search) = &mut request.search {
+                search.time_limit_ms = search.time_limit_ms.map(|ms| ms / group_count);
+            }
+
+            Optimizer { request }.optimize_group(&monitor.for_material(material.clone()))
+        });
+
+        for (material, outcome) in groups.into_iter().zip(outcomes) {
+            let result = outcome?;
+
+            layouts.extend(result.layouts);
+            optional_items_used.extend(result.optional_items_used);
+            offcuts_used.extend(result.offcuts_used);
+            materials.push(MaterialResult {
+                material,
+                panels_required: result.panels_required,
+                summary: result.summary,
+            });
+        }
+
+        let panels_required = self.count_panels(&layouts);
+        let mut summary = self.calculate_summary(&layouts);
+        if self.request.exact {
+            // Group bounds add up, and the total is optimal if every group is
+            let lower_bound: u32 = materials.iter().filter_map(|m| m.summary.lower_bound).sum();
+            summary.lower_bound = Some(lower_bound);
+            summary.optimality_proven = Some(
+                materials
+                    .iter()
+                    .all(|m| m.summary.optimality_proven == Some(true)),
+            );
+            summary.optimality_gap = Some(summary.total_panels.saturating_sub(lower_bound));
+        }
+
+        Ok(OptimizationResult {
+            panels_required,
+            layouts,
+            summary,
+            optional_items_used,
+            offcuts_used,
+            materials,
+            cancelled: false,
+        })
+    }
+
+    /// Lists the distinct item materials in order of first appearance.
+    fn material_groups(&self) -> Vec<Option<String>> {
+        let mut groups: Vec<Option<String>> = Vec::new();
+        for item in &self.request.items {
+            if !groups.contains(&item.material) {
+                groups.push(item.material.clone());
+            }
+        }
+        groups
+    }
+
+    /// Narrows the request to the items, panel types and offcuts of one material.
+    pub(super) fn group_request(&self, material: &Option<String>) -> OptimizationRequest {
+        let panel_types: Vec<PanelType> = self
+            .request
+            .panel_types
+            .iter()
+            .filter(|p| p.material == *material)
+            .cloned()
+            .collect();
+        let items = self
+            .request
+            .items
+            .iter()
+            .filter(|i| i.material == *material)
+            .cloned()
+            .collect();
+        let offcuts = self
+            .request
+            .offcuts
+            .iter()
+            .filter(|o| panel_types.iter().any(|p| p.id == o.panel_type_id))
+            .cloned()
+            .collect();
+
+        OptimizationRequest {
+            panel_types,
+            items,
+            offcuts,
+            ..self.request.clone()
+        }
+    }
+}
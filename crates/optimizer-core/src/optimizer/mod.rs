@@ -1,111 +1,291 @@
 use crate::types::*;
+use packing::PackedPanel;
+pub use progress::CancelToken;
+use progress::Monitor;
+use std::borrow::Borrow;
 use std::cmp::Ordering;
-
+use std::collections::HashMap;
+pub use validation::validate;
+
+mod cost;
+mod edit;
+mod exact;
+mod grain;
+mod guillotine;
 mod layout;
+mod material;
+mod offcut;
 mod optional;
+mod packing;
+mod parallel;
+mod progress;
+mod search;
 mod summary;
+
 #[cfg(test)]
 mod tests;
+mod validation;
 
 /// Packs rectangular items on panels using a best-fit decreasing heuristic.
 pub struct Optimizer {
     request: OptimizationRequest,
 }
 
+/// Best outcome of the sort strategies seen so far.
+#[derive(Default)]
+struct Candidates<'a> {
+    /// Best cuttable layouts and the item order that produced them.
+    best: Option<(Vec<PanelLayout>, &'a Vec<Item>)>,
+    /// Stock error that leaves the fewest items unplaced.
+    error: Option<OptimizerError>,
+    /// Whether a packing was rejected for not being guillotine-cuttable.
+    rejected: bool,
+}
+
 impl Optimizer {
     /// Validates requests and builds a new optimizer instance.
+    /// Fails with `InvalidInput` listing every invalid field (see `validate`),
+    /// or with `CannotFitAll` if some items fit no panel type.
     pub fn new(request: OptimizationRequest) -> Result<Self> {
-        if request.panel_types.is_empty() {
-            return Err(OptimizerError::InvalidInput(
-                "At least one panel type must be provided".to_string(),
-            ));
-        }
+        let optimizer = Self { request };
 
-        if request.items.is_empty() {
-            return Err(OptimizerError::InvalidInput(
-                "At least one item must be provided".to_string(),
-            ));
+        let mut report = ValidationReport::default();
+        optimizer.check_fields(&mut report);
+        if !report.is_valid() {
+            return Err(OptimizerError::InvalidInput(report.errors));
         }
 
-        for panel in &request.panel_types {
-            if panel.trimming < 0.0 {
-                return Err(OptimizerError::InvalidInput(format!(
-                    "Panel '{}' has negative trimming",
-                    panel.id
-                )));
-            }
+        optimizer.check_item_sizes()?;
+        Ok(optimizer)
+    }
 
-            let usable_width = panel.width - (panel.trimming * 2.0);
-            let usable_height = panel.height - (panel.trimming * 2.0);
+    /// Executes the full optimization flow and returns packed layouts.
+    ///
+    /// Each material group is packed on its own (see `optimize_materials`).
+    /// Within a group the BFD heuristic runs with several sort/rotation
+    /// strategies, each result is consolidated by redistributing items from the
+    /// least-used panel into the remaining ones, and the result that scores
+    /// best for the requested objective (fewest panels by default, lowest cost
+    /// for `Objective::Cost`) is kept. With `search` set, a budgeted local
+    /// search then tries to improve on it, and with `exact` set a branch-and-bound
+    /// search tries to prove the panel count optimal.
+    pub fn optimize(&self) -> Result<OptimizationResult> {
+        self.run(&Monitor::silent())
+    }
 
-            if usable_width <= 0.0 || usable_height <= 0.0 {
-                return Err(OptimizerError::InvalidInput(format!(
-                    "Panel '{}' becomes unusable after applying trimming",
-                    panel.id
-                )));
-            }
+    /// Runs `optimize`, reporting each phase to `on_progress` and stopping
+    /// early once `cancel` is set.
+    ///
+    /// A cancelled run skips the remaining strategies, search and optional
+    /// items and returns the best complete solution found so far, marked as
+    /// `cancelled`. Every item is still placed: when nothing was packed yet,
+    /// the next strategy runs to completion first.
+    pub fn optimize_with_progress<F>(
+        &self,
+        on_progress: F,
+        cancel: &CancelToken,
+    ) -> Result<OptimizationResult>
+    where
+        F: Fn(&Progress) + Sync,
+    {
+        let mut result = self.run(&Monitor::new(&on_progress, cancel))?;
+        result.cancelled = cancel.is_cancelled();
+        Ok(result)
+    }
+
+    fn run(&self, monitor: &Monitor) -> Result<OptimizationResult> {
+        if self.uses_materials() {
+            return self.optimize_materials(monitor);
         }
 
-        Ok(Self { request })
+        self.optimize_group(monitor)
     }
 
-    /// Executes the full optimization flow and returns packed layouts.
-    ///
-    /// Runs the BFD heuristic with several sort/rotation strategies, keeps the
-    /// result that uses the fewest panels (ties broken by lowest waste), then
-    /// attempts to consolidate by redistributing items from the least-used
-    /// panel into the remaining ones.
-    pub fn optimize(&self) -> Result<OptimizationResult> {
+    /// Packs all items of the request onto its panel types.
+    fn optimize_group(&self, monitor: &Monitor) -> Result<OptimizationResult> {
         let expanded_items = self.expand_items();
         let strategies = self.generate_sort_strategies(&expanded_items);
+        let total = strategies.len();
 
-        let mut best_layouts: Option<Vec<PanelLayout>> = None;
-        let mut best_panel_count = usize::MAX;
-        let mut best_waste = f64::MAX;
-
-        for sorted_items in &strategies {
-            match self.best_fit_decreasing_optimize(sorted_items) {
-                Ok(layouts) => {
-                    let layouts = self.try_reduce_panels(layouts, &expanded_items);
-                    let panel_count = layouts.len();
-                    let summary = self.calculate_summary(&layouts);
-                    let waste = summary.waste_area;
-
-                    if panel_count < best_panel_count
-                        || (panel_count == best_panel_count && waste < best_waste)
-                    {
-                        best_layouts = Some(layouts);
-                        best_panel_count = panel_count;
-                        best_waste = waste;
-                    }
-                }
-                Err(_) => continue,
+        let mut candidates = Candidates::default();
+
+        let numbered: Vec<(usize, &Vec<Item>)> = strategies.iter().enumerate().collect();
+        let outcomes = parallel::map_ordered(&numbered, |&(index, sorted_items)| {
+            if monitor.is_cancelled() {
+                return None;
             }
+            Some(self.pack_strategy(sorted_items, &expanded_items, index + 1, total, monitor))
+        });
+
+        for ((index, sorted_items), outcome) in numbered.into_iter().zip(outcomes) {
+            let outcome = match outcome {
+                Some(outcome) => outcome,
+                // A cancelled run still needs one complete solution
+                None if candidates.best.is_none() => {
+                    self.pack_strategy(sorted_items, &expanded_items, index + 1, total, monitor)
+                }
+                None => continue,
+            };
+            self.offer_candidate(&mut candidates, sorted_items, outcome);
+        }
+
+        let (mut layouts, best_order) = self.best_candidate(candidates)?;
+
+        if let Some(options) = &self.request.search {
+            layouts = self.search(options, best_order, layouts, &expanded_items, monitor);
         }
 
-        let layouts = best_layouts.ok_or(OptimizerError::CannotFitAll)?;
-        let (mut final_layouts, optional_items_used) = self.try_add_optional_items(layouts)?;
+        let exact = if self.request.exact {
+            let (exact_layouts, report) = self.exact_search(&expanded_items, layouts, monitor);
+            layouts = exact_layouts;
+            Some(report)
+        } else {
+            None
+        };
+
+        let (mut final_layouts, optional_items_used) =
+            self.try_add_optional_items(layouts, monitor)?;
+
+        self.finalize_placements(&mut final_layouts, &expanded_items);
 
-        // Compute unused areas for each panel in the final output
+        // Compute unused areas (and the cut sequence in guillotine mode) for each panel
         for layout in &mut final_layouts {
             layout.unused_areas = self.compute_output_unused_areas(layout);
+            if self.request.guillotine {
+                layout.cuts = self
+                    .compute_guillotine_cuts(layout)
+                    .expect("candidate layouts are only accepted when guillotine-cuttable");
+            }
         }
 
         let panels_required = self.count_panels(&final_layouts);
-        let summary = self.calculate_summary(&final_layouts);
+        let mut summary = self.calculate_summary(&final_layouts);
+        if let Some(report) = exact {
+            summary.lower_bound = Some(report.lower_bound);
+            summary.optimality_proven = Some(report.proven);
+            summary.optimality_gap = Some(summary.total_panels.saturating_sub(report.lower_bound));
+        }
+        let offcuts_used = final_layouts
+            .iter()
+            .filter_map(|l| l.offcut_id.clone())
+            .collect();
 
         Ok(OptimizationResult {
             panels_required,
             layouts: final_layouts,
             summary,
             optional_items_used,
+            offcuts_used,
+            materials: Vec::new(),
+            cancelled: false,
         })
     }
 
+    /// Runs `pack_order` for sort strategy `index` of `total`, reporting both
+    /// phases and the resulting panel count.
+    fn pack_strategy(
+        &self,
+        items: &[Item],
+        expanded_items: &[Item],
+        index: usize,
+        total: usize,
+        monitor: &Monitor,
+    ) -> Result<Vec<PanelLayout>> {
+        monitor.report(Phase::Strategy { index, total });
+        let layouts = self.best_fit_decreasing_optimize(items)?;
+        monitor.report(Phase::PanelReduction { index, total });
+        let layouts = self.consolidate_panels(layouts, expanded_items);
+        self.record_solution(monitor, &layouts, expanded_items);
+        Ok(layouts)
+    }
+
+    /// Keeps the outcome of a sort strategy if it beats the candidates so far.
+    fn offer_candidate<'a>(
+        &self,
+        candidates: &mut Candidates<'a>,
+        order: &'a Vec<Item>,
+        outcome: Result<Vec<PanelLayout>>,
+    ) {
+        match outcome {
+            // Rejected: not every panel can be cut with edge-to-edge cuts
+            Ok(layouts) if !self.is_cuttable(&layouts) => candidates.rejected = true,
+            Ok(layouts) => {
+                let is_better = match &candidates.best {
+                    None => true,
+                    Some((best, _)) => self.compare_layouts(&layouts, best) == Ordering::Less,
+                };
+
+                if is_better {
+                    candidates.best = Some((layouts, order));
+                }
+            }
+            Err(err) => {
+                // Keep the stock error that leaves the fewest items unplaced
+                let replace = match (&candidates.error, &err) {
+                    (None, _) => true,
+                    (
+                        Some(OptimizerError::InsufficientStock {
+                            unplaced_items: best,
+                        }),
+                        OptimizerError::InsufficientStock { unplaced_items },
+                    ) => unplaced_items.len() < best.len(),
+                    _ => false,
+                };
+                if replace {
+                    candidates.error = Some(err);
+                }
+            }
+        }
+    }
+
+    /// Returns the best layouts and their item order, or why there are none.
+    fn best_candidate<'a>(
+        &self,
+        candidates: Candidates<'a>,
+    ) -> Result<(Vec<PanelLayout>, &'a Vec<Item>)> {
+        match candidates {
+            Candidates {
+                best: Some(best), ..
+            } => Ok(best),
+            Candidates { rejected: true, .. } => Err(OptimizerError::NotGuillotine),
+            Candidates {
+                error: Some(err), ..
+            } => Err(err),
+            _ => Err(self.cannot_fit(&[])),
+        }
+    }
+
+    /// Packs items in the given order with BFD, then consolidates panels.
+    fn pack_order(&self, items: &[Item], expanded_items: &[Item]) -> Result<Vec<PanelLayout>> {
+        let layouts = self.best_fit_decreasing_optimize(items)?;
+        Ok(self.consolidate_panels(layouts, expanded_items))
+    }
+
+    /// Removes panels whose items fit elsewhere (and moves panels to cheaper
+    /// types when optimizing for cost).
+    fn consolidate_panels(
+        &self,
+        layouts: Vec<PanelLayout>,
+        expanded_items: &[Item],
+    ) -> Vec<PanelLayout> {
+        let mut layouts = self.try_reduce_panels(layouts, expanded_items);
+        if self.request.objective == Objective::Cost {
+            layouts = self.try_downgrade_panels(layouts, expanded_items);
+        }
+        layouts
+    }
+
     /// Duplicates items according to their requested quantity.
+    /// Expanded items carry the cut size: finished size minus edge banding.
     fn expand_items(&self) -> Vec<Item> {
         let mut expanded = Vec::new();
         for item in &self.request.items {
+            let (width_reduction, height_reduction) = item
+                .banding
+                .as_ref()
+                .map(|b| (b.width_reduction(), b.height_reduction()))
+                .unwrap_or((0.0, 0.0));
+
             for i in 0..item.quantity {
                 expanded.push(Item {
                     id: if item.quantity > 1 {
@@ -113,10 +293,14 @@ impl Optimizer {
                     } else {
                         item.id.clone()
                     },
-                    width: item.width,
-                    height: item.height,
+                    width: item.width - width_reduction,
+                    height: item.height - height_reduction,
                     quantity: 1,
                     can_rotate: item.can_rotate,
+                    material: item.material.clone(),
+                    grain: item.grain,
+                    banding: item.banding.clone(),
+                    notes: None,
                 });
             }
         }
@@ -135,11 +319,10 @@ impl Optimizer {
                 .map(|item| {
                     if item.can_rotate && item.width > item.height {
                         Item {
-                            id: item.id.clone(),
                             width: item.height,
                             height: item.width,
-                            quantity: item.quantity,
-                            can_rotate: item.can_rotate,
+                            grain: item.grain.rotated(),
+                            ..item.clone()
                         }
                     } else {
                         item.clone()
@@ -155,11 +338,10 @@ impl Optimizer {
                 .map(|item| {
                     if item.can_rotate && item.height > item.width {
                         Item {
-                            id: item.id.clone(),
                             width: item.height,
                             height: item.width,
-                            quantity: item.quantity,
-                            can_rotate: item.can_rotate,
+                            grain: item.grain.rotated(),
+                            ..item.clone()
                         }
                     } else {
                         item.clone()
@@ -230,8 +412,9 @@ impl Optimizer {
     }
 
     /// After the initial BFD pass, try to eliminate the least-used panel by
-    /// redistributing its items across the remaining panels. Repeat until
-    /// no more panels can be removed.
+    /// redistributing its items across the remaining panels (offcuts included).
+    /// Repeat until no more panels can be removed. Only existing panels are
+    /// reused, so panel stock limits stay satisfied. Offcuts are never removed.
     fn try_reduce_panels(
         &self,
         layouts: Vec<PanelLayout>,
@@ -241,60 +424,31 @@ impl Optimizer {
             return layouts;
         }
 
-        let mut current = layouts;
+        let mut current = self.pack_panels(layouts);
 
         loop {
             if current.len() <= 1 {
                 break;
             }
 
-            // Find the panel with the smallest used area (best candidate for removal)
-            let min_idx = current
+            // Find the new panel with the smallest used area (best candidate for removal)
+            let Some(min_idx) = current
                 .iter()
+                .map(PackedPanel::layout)
                 .enumerate()
+                .filter(|(_, l)| l.offcut_id.is_none())
                 .min_by(|(_, a), (_, b)| {
                     let used_a: f64 = a.placements.iter().map(|p| p.width * p.height).sum();
                     let used_b: f64 = b.placements.iter().map(|p| p.width * p.height).sum();
                     used_a.partial_cmp(&used_b).unwrap_or(Ordering::Equal)
                 })
                 .map(|(i, _)| i)
-                .unwrap();
-
-            let target_panel = current[min_idx].clone();
-
-            // Reconstruct Item structs from the panel's placements
-            let mut items_to_place: Vec<Item> = target_panel
-                .placements
-                .iter()
-                .map(|p| {
-                    let (orig_w, orig_h) = if p.rotated {
-                        (p.height, p.width)
-                    } else {
-                        (p.width, p.height)
-                    };
-
-                    let can_rotate = expanded_items
-                        .iter()
-                        .find(|i| i.id == p.item_id)
-                        .map(|i| i.can_rotate)
-                        .unwrap_or(true);
-
-                    Item {
-                        id: p.item_id.clone(),
-                        width: orig_w,
-                        height: orig_h,
-                        quantity: 1,
-                        can_rotate,
-                    }
-                })
-                .collect();
+            else {
+                break;
+            };
 
-            // Place largest items first for best fit
-            items_to_place.sort_by(|a, b| {
-                let area_a = a.width * a.height;
-                let area_b = b.width * b.height;
-                area_b.partial_cmp(&area_a).unwrap_or(Ordering::Equal)
-            });
+            let items_to_place =
+                self.items_from_placements(&current[min_idx].layout().placements, expanded_items);
 
             // Remove the target panel and try to redistribute its items
             let mut test = current.clone();
@@ -302,28 +456,8 @@ impl Optimizer {
 
             let mut all_placed = true;
             for item in &items_to_place {
-                let mut best_fit: Option<(usize, Placement, f64)> = None;
-
-                for (idx, layout) in test.iter().enumerate() {
-                    if let Some((placement, score)) = self.find_best_placement(item, layout) {
-                        let adjusted_score = if self.request.min_initial_usage {
-                            score + (idx as f64) * 1_000_000.0
-                        } else {
-                            score
-                        };
-
-                        match best_fit {
-                            None => best_fit = Some((idx, placement, adjusted_score)),
-                            Some((_, _, best_score)) if adjusted_score < best_score => {
-                                best_fit = Some((idx, placement, adjusted_score));
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-
-                if let Some((idx, placement, _)) = best_fit {
-                    test[idx].placements.push(placement);
+                if let Some((idx, placement)) = self.best_fit_on_layouts(item, &test) {
+                    self.place(&mut test[idx], placement);
                 } else {
                     all_placed = false;
                     break;
@@ -339,14 +473,103 @@ impl Optimizer {
         }
 
         // Renumber panels per panel type
+        let mut current = self.unpack_panels(current);
         self.renumber_panels(&mut current);
         current
     }
 
+    /// Reconstructs Item structs from placements, largest first, so they can be
+    /// packed again elsewhere. Known items keep their original orientation and grain.
+    fn items_from_placements(
+        &self,
+        placements: &[Placement],
+        expanded_items: &[Item],
+    ) -> Vec<Item> {
+        let by_id = items_by_id(expanded_items);
+        let mut items: Vec<Item> = placements
+            .iter()
+            .map(|p| {
+                if let Some(&item) = by_id.get(p.item_id.as_str()) {
+                    return item.clone();
+                }
+
+                let (orig_w, orig_h) = if p.rotated {
+                    (p.height, p.width)
+                } else {
+                    (p.width, p.height)
+                };
+
+                Item {
+                    id: p.item_id.clone(),
+                    width: orig_w,
+                    height: orig_h,
+                    quantity: 1,
+                    can_rotate: true,
+                    material: None,
+                    grain: Grain::None,
+                    banding: None,
+                    notes: None,
+                }
+            })
+            .collect();
+
+        // Place largest items first for best fit
+        items.sort_by(|a, b| {
+            let area_a = a.width * a.height;
+            let area_b = b.width * b.height;
+            area_b.partial_cmp(&area_a).unwrap_or(Ordering::Equal)
+        });
+
+        items
+    }
+
+    /// Makes placement details refer to the items as requested: sort strategies
+    /// may pre-rotate items, so `rotated` is recomputed from the original item
+    /// dimensions, the panel grain is recorded relative to each part, and edge
+    /// banding is turned along with rotated parts.
+    fn finalize_placements(&self, layouts: &mut [PanelLayout], expanded_items: &[Item]) {
+        let eps = 1e-6;
+        let by_id = items_by_id(expanded_items);
+
+        for layout in layouts.iter_mut() {
+            let panel_grain = self.layout_grain(layout);
+
+            for placement in &mut layout.placements {
+                if let Some(&item) = by_id.get(placement.item_id.as_str()) {
+                    // Square items look the same either way, so keep the recorded flag
+                    if (item.width - item.height).abs() > eps {
+                        placement.rotated = (placement.width - item.width).abs() > eps
+                            || (placement.height - item.height).abs() > eps;
+                    }
+
+                    placement.banding = item.banding.as_ref().map(|banding| {
+                        if placement.rotated {
+                            banding.rotated()
+                        } else {
+                            banding.clone()
+                        }
+                    });
+                }
+
+                placement.grain = if placement.rotated {
+                    panel_grain.rotated()
+                } else {
+                    panel_grain
+                };
+            }
+        }
+    }
+
     /// Reassigns sequential panel_number values per panel_type_id.
+    /// Offcut layouts are identified by their offcut id and keep number 1.
     fn renumber_panels(&self, layouts: &mut [PanelLayout]) {
         let mut type_counts: Vec<(String, u32)> = Vec::new();
         for layout in layouts.iter_mut() {
+            if layout.offcut_id.is_some() {
+                layout.panel_number = 1;
+                continue;
+            }
+
             let num = if let Some((_, count)) = type_counts
                 .iter_mut()
                 .find(|(id, _)| *id == layout.panel_type_id)
@@ -363,48 +586,29 @@ impl Optimizer {
 
     /// Places items using best-fit decreasing with bottom-left placement strategy.
     /// Items are placed as far left and down as possible to minimize fragmentation.
+    /// Items that fit no open panel go to an unused offcut before a new panel is opened.
+    /// Items that only fit panel types whose stock is used up are collected and
+    /// reported together as `OptimizerError::InsufficientStock`.
     fn best_fit_decreasing_optimize(&self, items: &[Item]) -> Result<Vec<PanelLayout>> {
-        let mut layouts = Vec::new();
+        let mut layouts: Vec<PackedPanel> = Vec::new();
+        let mut unplaced_items = Vec::new();
 
         for item in items {
-            let mut best_fit: Option<(usize, Placement, f64)> = None;
-
             // Try to place on existing panels using bottom-left-fill strategy
-            for (idx, layout) in layouts.iter().enumerate() {
-                if let Some((placement, score)) = self.find_best_placement(item, layout) {
-                    // When min_initial_usage is set, strongly prefer filling earlier panels
-                    let adjusted_score = if self.request.min_initial_usage {
-                        // Add large penalty for using later panels to encourage filling earlier ones
-                        score + (idx as f64) * 1_000_000.0
-                    } else {
-                        score
-                    };
-
-                    match best_fit {
-                        None => {
-                            best_fit = Some((idx, placement, adjusted_score));
-                        }
-                        Some((_, _, best_score)) => {
-                            if adjusted_score < best_score {
-                                best_fit = Some((idx, placement, adjusted_score));
-                            }
-                        }
-                    }
-                }
-            }
-
-            if let Some((idx, placement, _)) = best_fit {
-                layouts[idx].placements.push(placement);
+            if let Some((idx, placement)) = self.best_fit_on_layouts(item, &layouts) {
+                self.place(&mut layouts[idx], placement);
+            } else if let Some(offcut_layout) = self.place_on_offcut(item, &layouts) {
+                layouts.push(self.pack_panel(offcut_layout));
             } else if let Some((panel_type, panel_width, panel_height, placement)) =
-                self.place_on_new_panel(item)?
+                self.place_on_new_panel(item, &layouts)?
             {
-                let panel_number = layouts
-                    .iter()
+                let panel_number = self
+                    .new_panels(&layouts)
                     .filter(|l| l.panel_type_id == panel_type.id)
                     .count() as u32
                     + 1;
 
-                layouts.push(PanelLayout {
+                layouts.push(self.pack_panel(PanelLayout {
                     panel_type_id: panel_type.id.clone(),
                     panel_number,
                     width: panel_width,
@@ -412,22 +616,71 @@ impl Optimizer {
                     trimming: panel_type.trimming,
                     placements: vec![placement],
                     unused_areas: Vec::new(), // Populated after optimization completes
-                });
+                    cuts: Vec::new(),
+                    offcut_id: None,
+                }));
+            } else {
+                unplaced_items.push(item.id.clone());
             }
         }
 
-        Ok(layouts)
+        if !unplaced_items.is_empty() {
+            return Err(OptimizerError::InsufficientStock { unplaced_items });
+        }
+
+        Ok(self.unpack_panels(layouts))
+    }
+
+    /// Picks the open panel and position that score best for an item.
+    /// Panels are scored independently (in parallel with the `parallel`
+    /// feature) and ties go to the earliest panel.
+    fn best_fit_on_layouts(
+        &self,
+        item: &Item,
+        panels: &[PackedPanel],
+    ) -> Option<(usize, Placement)> {
+        let candidates =
+            parallel::map_ordered(panels, |panel| self.find_best_placement(item, panel));
+
+        let mut best_fit: Option<(usize, Placement, f64)> = None;
+        for (idx, candidate) in candidates.into_iter().enumerate() {
+            let Some((placement, score)) = candidate else {
+                continue;
+            };
+
+            // When min_initial_usage is set, strongly prefer filling earlier panels
+            let adjusted_score = if self.request.min_initial_usage {
+                // Add large penalty for using later panels to encourage filling earlier ones
+                score + (idx as f64) * 1_000_000.0
+            } else {
+                score
+            };
+
+            match best_fit {
+                None => best_fit = Some((idx, placement, adjusted_score)),
+                Some((_, _, best_score)) if adjusted_score < best_score => {
+                    best_fit = Some((idx, placement, adjusted_score));
+                }
+                _ => {}
+            }
+        }
+
+        best_fit.map(|(idx, placement, _)| (idx, placement))
     }
 
     /// Finds the best placement position for an item on a panel using bottom-left-fill.
     /// Returns the placement and a score (lower is better).
-    fn find_best_placement(&self, item: &Item, layout: &PanelLayout) -> Option<(Placement, f64)> {
-        let unused_areas = self.find_unused_areas(layout);
+    fn find_best_placement(&self, item: &Item, panel: &PackedPanel) -> Option<(Placement, f64)> {
+        let layout = panel.layout();
+        let panel_grain = self.layout_grain(layout);
         let mut best: Option<(Placement, f64)> = None;
 
-        for area in &unused_areas {
+        for area in panel.unused_areas() {
             // Try normal orientation
-            if item.width <= area.width && item.height <= area.height {
+            if self.orientation_allowed(item, panel_grain, false)
+                && item.width <= area.width
+                && item.height <= area.height
+            {
                 let score = self.calculate_placement_score(
                     area.x,
                     area.y,
@@ -443,6 +696,8 @@ impl Optimizer {
                     width: item.width,
                     height: item.height,
                     rotated: false,
+                    grain: Grain::None,
+                    banding: None,
                 };
 
                 match best {
@@ -455,7 +710,10 @@ impl Optimizer {
             }
 
             // Try rotated orientation
-            if item.can_rotate && item.height <= area.width && item.width <= area.height {
+            if self.orientation_allowed(item, panel_grain, true)
+                && item.height <= area.width
+                && item.width <= area.height
+            {
                 let score = self.calculate_placement_score(
                     area.x,
                     area.y,
@@ -471,6 +729,8 @@ impl Optimizer {
                     width: item.height,
                     height: item.width,
                     rotated: true,
+                    grain: Grain::None,
+                    banding: None,
                 };
 
                 match best {
@@ -635,23 +895,27 @@ impl Optimizer {
     }
 
     /// Enumerates every feasible placement for an item on a specific panel.
-    /// Used by optional item placement.
+    /// Used by optional item placement and the exact search.
     fn generate_candidate_placements(
         &self,
         item: &Item,
-        layout: &PanelLayout,
+        panel: &PackedPanel,
     ) -> Vec<(Placement, f64, f64, f64)> {
-        let unused_areas = self.find_unused_areas(layout);
+        let layout = panel.layout();
+        let panel_grain = self.layout_grain(layout);
         let mut candidates = Vec::new();
 
-        for area in unused_areas {
-            if item.width <= area.width && item.height <= area.height {
+        for area in panel.unused_areas() {
+            if self.orientation_allowed(item, panel_grain, false)
+                && item.width <= area.width
+                && item.height <= area.height
+            {
                 let score = self.calculate_placement_score(
                     area.x,
                     area.y,
                     item.width,
                     item.height,
-                    &area,
+                    area,
                     layout,
                 );
                 candidates.push((
@@ -662,6 +926,8 @@ impl Optimizer {
                         width: item.width,
                         height: item.height,
                         rotated: false,
+                        grain: Grain::None,
+                        banding: None,
                     },
                     score,
                     area.width,
@@ -669,13 +935,16 @@ impl Optimizer {
                 ));
             }
 
-            if item.can_rotate && item.height <= area.width && item.width <= area.height {
+            if self.orientation_allowed(item, panel_grain, true)
+                && item.height <= area.width
+                && item.width <= area.height
+            {
                 let score = self.calculate_placement_score(
                     area.x,
                     area.y,
                     item.height,
                     item.width,
-                    &area,
+                    area,
                     layout,
                 );
                 candidates.push((
@@ -686,6 +955,8 @@ impl Optimizer {
                         width: item.height,
                         height: item.width,
                         rotated: true,
+                        grain: Grain::None,
+                        banding: None,
                     },
                     score,
                     area.width,
@@ -698,30 +969,29 @@ impl Optimizer {
     }
 
     /// Opens a new panel when the item cannot be placed on existing layouts.
-    fn place_on_new_panel(&self, item: &Item) -> Result<Option<(PanelType, f64, f64, Placement)>> {
+    /// Returns `Ok(None)` when the item fits some panel type but none of those
+    /// types has stock left, and `CannotFitAll` when it fits no panel type at all.
+    fn place_on_new_panel<L: Borrow<PanelLayout>>(
+        &self,
+        item: &Item,
+        layouts: &[L],
+    ) -> Result<Option<(PanelType, f64, f64, Placement)>> {
         let mut best_candidate: Option<(PanelType, f64, f64, Placement, f64, u32)> = None;
+        let mut fits_any_type = false;
 
         for panel_type in &self.request.panel_types {
-            let orientations = if (panel_type.width - panel_type.height).abs() < f64::EPSILON {
-                vec![(panel_type.width, panel_type.height)]
-            } else {
-                vec![
-                    (panel_type.width, panel_type.height),
-                    (panel_type.height, panel_type.width),
-                ]
-            };
-
-            for (panel_width, panel_height) in orientations {
+            for (panel_width, panel_height) in self.panel_orientations(panel_type) {
                 if let Some((placement, score)) =
                     self.best_new_panel_placement(item, panel_type, panel_width, panel_height)
                 {
-                    let capacity = self.estimate_panel_capacity(
-                        item,
-                        panel_width,
-                        panel_height,
-                        panel_type.trimming,
-                    );
-                    match best_candidate {
+                    fits_any_type = true;
+                    if !self.has_stock(panel_type, layouts) {
+                        continue;
+                    }
+
+                    let capacity =
+                        self.estimate_panel_capacity(item, panel_type, panel_width, panel_height);
+                    match &best_candidate {
                         None => {
                             best_candidate = Some((
                                 panel_type.clone(),
@@ -732,10 +1002,23 @@ impl Optimizer {
                                 capacity,
                             ));
                         }
-                        Some((_, _, _, _, best_score, best_capacity)) => {
-                            if capacity > best_capacity
-                                || (capacity == best_capacity && score < best_score)
-                            {
+                        Some((best_type, _, _, _, best_score, best_capacity)) => {
+                            let is_better = if self.request.objective == Objective::Cost {
+                                // Cheapest price per copy of this item that fits the sheet
+                                let unit_cost =
+                                    self.panel_cost(panel_type) / capacity.max(1) as f64;
+                                let best_unit_cost =
+                                    self.panel_cost(best_type) / (*best_capacity).max(1) as f64;
+                                unit_cost < best_unit_cost
+                                    || (unit_cost == best_unit_cost
+                                        && (capacity > *best_capacity
+                                            || (capacity == *best_capacity && score < *best_score)))
+                            } else {
+                                capacity > *best_capacity
+                                    || (capacity == *best_capacity && score < *best_score)
+                            };
+
+                            if is_better {
                                 best_candidate = Some((
                                     panel_type.clone(),
                                     panel_width,
@@ -755,7 +1038,72 @@ impl Optimizer {
             return Ok(Some((panel_type, panel_width, panel_height, placement)));
         }
 
-        Err(OptimizerError::CannotFitAll)
+        if fits_any_type {
+            return Ok(None);
+        }
+
+        Err(self.cannot_fit(&[item]))
+    }
+
+    /// Builds a `CannotFitAll` error, naming the panel type with the largest usable area
+    /// among those of the items' materials (any panel type if no items are given).
+    fn cannot_fit(&self, items: &[&Item]) -> OptimizerError {
+        let largest_panel = self
+            .request
+            .panel_types
+            .iter()
+            .filter(|p| items.is_empty() || items.iter().any(|i| i.material == p.material))
+            .map(|p| PanelSize {
+                panel_type_id: p.id.clone(),
+                width: p.width - p.trimming * 2.0,
+                height: p.height - p.trimming * 2.0,
+            })
+            .max_by(|a, b| {
+                (a.width * a.height)
+                    .partial_cmp(&(b.width * b.height))
+                    .unwrap_or(Ordering::Equal)
+            });
+
+        OptimizerError::CannotFitAll {
+            items: items
+                .iter()
+                .map(|item| ItemSize {
+                    id: item.id.clone(),
+                    width: item.width,
+                    height: item.height,
+                })
+                .collect(),
+            largest_panel,
+        }
+    }
+
+    /// Returns true if another sheet of this panel type may still be opened.
+    fn has_stock<L: Borrow<PanelLayout>>(&self, panel_type: &PanelType, layouts: &[L]) -> bool {
+        match panel_type.available {
+            None => true,
+            Some(available) => {
+                let used = self
+                    .new_panels(layouts)
+                    .filter(|l| l.panel_type_id == panel_type.id)
+                    .count();
+                used < available as usize
+            }
+        }
+    }
+
+    /// Lists the (width, height) orientations a fresh panel of this type may be used in.
+    /// Panels with grain are only used as specified so their grain stays put.
+    fn panel_orientations(&self, panel_type: &PanelType) -> Vec<(f64, f64)> {
+        if !panel_type.grain.is_none()
+            || (panel_type.width - panel_type.height).abs() < f64::EPSILON
+        {
+            vec![(panel_type.width, panel_type.height)]
+        } else {
+            vec![
+                (panel_type.width, panel_type.height),
+                (panel_type.height, panel_type.width),
+            ]
+        }
     }
 
     /// Picks the best placement for an item on a fresh panel orientation.
@@ -781,6 +1129,8 @@ impl Optimizer {
             trimming: panel_type.trimming,
             placements: Vec::new(),
             unused_areas: Vec::new(),
+            cuts: Vec::new(),
+            offcut_id: None,
         };
 
         let area = layout::UnusedArea {
@@ -792,7 +1142,10 @@ impl Optimizer {
 
         let mut best: Option<(Placement, f64)> = None;
 
-        if item.width <= usable_width && item.height <= usable_height {
+        if self.orientation_allowed(item, panel_type.grain, false)
+            && item.width <= usable_width
+            && item.height <= usable_height
+        {
             let score = self.calculate_placement_score(
                 area.x,
                 area.y,
@@ -808,11 +1161,16 @@ impl Optimizer {
                 width: item.width,
                 height: item.height,
                 rotated: false,
+                grain: Grain::None,
+                banding: None,
             };
             best = Some((placement, score));
         }
 
-        if item.can_rotate && item.height <= usable_width && item.width <= usable_height {
+        if self.orientation_allowed(item, panel_type.grain, true)
+            && item.height <= usable_width
+            && item.width <= usable_height
+        {
             let score = self.calculate_placement_score(
                 area.x,
                 area.y,
@@ -828,6 +1186,8 @@ impl Optimizer {
                 width: item.height,
                 height: item.width,
                 rotated: true,
+                grain: Grain::None,
+                banding: None,
             };
 
             match best {
@@ -846,20 +1206,23 @@ impl Optimizer {
     fn estimate_panel_capacity(
         &self,
         item: &Item,
+        panel_type: &PanelType,
         panel_width: f64,
         panel_height: f64,
-        trimming: f64,
     ) -> u32 {
-        let usable_width = panel_width - (trimming * 2.0);
-        let usable_height = panel_height - (trimming * 2.0);
+        let usable_width = panel_width - (panel_type.trimming * 2.0);
+        let usable_height = panel_height - (panel_type.trimming * 2.0);
 
         if usable_width <= 0.0 || usable_height <= 0.0 {
             return 0;
         }
 
-        let capacity_normal =
-            self.capacity_for_dims(item.width, item.height, usable_width, usable_height);
-        let capacity_rotated = if item.can_rotate {
+        let capacity_normal = if self.orientation_allowed(item, panel_type.grain, false) {
+            self.capacity_for_dims(item.width, item.height, usable_width, usable_height)
+        } else {
+            0
+        };
+        let capacity_rotated = if self.orientation_allowed(item, panel_type.grain, true) {
             self.capacity_for_dims(item.height, item.width, usable_width, usable_height)
         } else {
             0
@@ -895,3 +1258,13 @@ impl Optimizer {
         cols.saturating_mul(rows)
     }
 }
+
+/// Indexes expanded items by id for lookups per placement; the first item
+/// with an id wins.
+fn items_by_id(expanded_items: &[Item]) -> HashMap<&str, &Item> {
+    let mut by_id = HashMap::with_capacity(expanded_items.len());
+    for item in expanded_items {
+        by_id.entry(item.id.as_str()).or_insert(item);
+    }
+    by_id
+}
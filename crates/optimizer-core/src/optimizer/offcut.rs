@@ -0,0 +1,89 @@
+use super::*;
+
+impl Optimizer {
+    /// Opens the highest priority offcut that still is unused and can hold the item.
+    /// Among offcuts of equal priority the smallest one that fits is chosen.
+    pub(super) fn place_on_offcut<L: Borrow<PanelLayout>>(
+        &self,
+        item: &Item,
+        layouts: &[L],
+    ) -> Option<PanelLayout> {
+        let mut offcuts: Vec<&Offcut> = self
+            .request
+            .offcuts
+            .iter()
+            .filter(|o| {
+                !layouts
+                    .iter()
+                    .any(|l| l.borrow().offcut_id.as_ref() == Some(&o.id))
+            })
+            .collect();
+        offcuts.sort_by(|a, b| {
+            b.priority.cmp(&a.priority).then_with(|| {
+                (a.width * a.height)
+                    .partial_cmp(&(b.width * b.height))
+                    .unwrap_or(Ordering::Equal)
+            })
+        });
+
+        for offcut in offcuts {
+            let Some(panel_type) = self.offcut_panel_type(offcut) else {
+                continue;
+            };
+
+            let best = self
+                .panel_orientations(&panel_type)
+                .into_iter()
+                .filter_map(|(width, height)| {
+                    self.best_new_panel_placement(item, &panel_type, width, height)
+                        .map(|(placement, score)| (width, height, placement, score))
+                })
+                .min_by(|a, b| a.3.partial_cmp(&b.3).unwrap_or(Ordering::Equal));
+
+            if let Some((width, height, placement, _)) = best {
+                return Some(PanelLayout {
+                    panel_type_id: panel_type.id,
+                    panel_number: 1,
+                    width,
+                    height,
+                    trimming: 0.0,
+                    placements: vec![placement],
+                    unused_areas: Vec::new(),
+                    cuts: Vec::new(),
+                    offcut_id: Some(offcut.id.clone()),
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Describes an offcut as an untrimmed panel of its source panel type.
+    pub(super) fn offcut_panel_type(&self, offcut: &Offcut) -> Option<PanelType> {
+        let source = self
+            .request
+            .panel_types
+            .iter()
+            .find(|p| p.id == offcut.panel_type_id)?;
+
+        Some(PanelType {
+            id: source.id.clone(),
+            width: offcut.width,
+            height: offcut.height,
+            trimming: 0.0,
+            grain: source.grain,
+            ..Default::default()
+        })
+    }
+
+    /// Returns only the layouts cut from new panels.
+    pub(super) fn new_panels<'a, L: Borrow<PanelLayout>>(
+        &self,
+        layouts: &'a [L],
+    ) -> impl Iterator<Item = &'a PanelLayout> {
+        layouts
+            .iter()
+            .map(Borrow::borrow)
+            .filter(|l| l.offcut_id.is_none())
+    }
+}
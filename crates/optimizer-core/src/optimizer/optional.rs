@@ -10,9 +10,11 @@ impl Optimizer {
     /// Optional items are only considered when effective waste exceeds 8%.
     /// Items are tried in descending priority order, and multiple copies of the same
     /// item type can be placed. The algorithm never adds panels for optional items.
+    /// Cancelling keeps the optional items placed so far.
     pub(super) fn try_add_optional_items(
         &self,
         layouts: Vec<PanelLayout>,
+        monitor: &Monitor,
     ) -> Result<(Vec<PanelLayout>, Vec<String>)> {
         let initial_summary = self.calculate_summary(&layouts);
         let effective_waste_pct = initial_summary
@@ -45,14 +47,16 @@ impl Optimizer {
             })
         });
 
-        let mut best_layouts = layouts.clone();
+        monitor.report(Phase::OptionalItems);
+        let mut best_panels = self.pack_panels(layouts.clone());
+        let mut best_layouts = layouts;
         let mut best_summary = initial_summary;
         let mut items_used: Vec<String> = Vec::new();
         let panel_count = best_layouts.len();
 
         // Keep trying to place optional items until no more can fit
         let mut made_progress = true;
-        while made_progress {
+        while made_progress && !monitor.is_cancelled() {
             made_progress = false;
 
             for (panel_type_id, optional_item) in &optional_items_pool {
@@ -64,14 +68,18 @@ impl Optimizer {
                         continue;
                     }
 
-                    let mut test_layouts = best_layouts.clone();
                     if let Some(placement) =
-                        self.try_place_item(&test_item, &test_layouts[layout_idx])
+                        self.try_place_item(&test_item, &best_panels[layout_idx])
                     {
-                        test_layouts[layout_idx].placements.push(placement);
+                        let mut test_panel = best_panels[layout_idx].clone();
+                        self.place(&mut test_panel, placement);
+                        let mut test_layouts = best_layouts.clone();
+                        test_layouts[layout_idx] = test_panel.layout().clone();
 
                         // Ensure we haven't somehow added a new panel (safety check)
-                        if test_layouts.len() > panel_count {
+                        if test_layouts.len() > panel_count
+                            || !self.is_cuttable(std::slice::from_ref(test_panel.layout()))
+                        {
                             continue;
                         }
 
@@ -84,6 +92,7 @@ impl Optimizer {
                             .unwrap_or(test_summary.waste_area);
 
                         if test_waste < best_waste {
+                            best_panels[layout_idx] = test_panel;
                             best_layouts = test_layouts;
                             best_summary = test_summary;
                             items_used.push(optional_item.id.clone());
@@ -105,8 +114,8 @@ impl Optimizer {
     }
 
     /// Returns the best scoring placement for an item on a layout, if any.
-    pub(super) fn try_place_item(&self, item: &Item, layout: &PanelLayout) -> Option<Placement> {
-        let candidates = self.generate_candidate_placements(item, layout);
+    pub(super) fn try_place_item(&self, item: &Item, panel: &PackedPanel) -> Option<Placement> {
+        let candidates = self.generate_candidate_placements(item, panel);
         candidates
             .into_iter()
             .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
@@ -121,6 +130,10 @@ impl Optimizer {
             height: opt.height,
             quantity: 1,
             can_rotate: opt.can_rotate,
+            material: None,
+            grain: opt.grain,
+            banding: None,
+            notes: None,
         }
     }
 }
@@ -0,0 +1,76 @@
+use super::layout::UnusedArea;
+use super::*;
+use std::borrow::Borrow;
+
+/// A panel being packed, together with its free rectangles.
+///
+/// The free list is split once per placement instead of being rebuilt from
+/// every placement on each query, so scoring an item against a panel no longer
+/// grows with the number of parts already on it. Placements are only added
+/// through `Optimizer::place`, which keeps the two in sync.
+#[derive(Debug, Clone)]
+pub(super) struct PackedPanel {
+    layout: PanelLayout,
+    free_rects: Vec<UnusedArea>,
+}
+
+impl PackedPanel {
+    /// The layout packed so far.
+    pub fn layout(&self) -> &PanelLayout {
+        &self.layout
+    }
+
+    /// Free rectangles large enough to be useful, in the same order as
+    /// `Optimizer::find_unused_areas` returns them for this layout.
+    pub fn unused_areas(&self) -> impl Iterator<Item = &UnusedArea> {
+        self.free_rects
+            .iter()
+            .filter(|r| r.width > 0.5 && r.height > 0.5)
+    }
+
+    pub fn into_layout(self) -> PanelLayout {
+        self.layout
+    }
+}
+
+impl Borrow<PanelLayout> for PackedPanel {
+    fn borrow(&self) -> &PanelLayout {
+        &self.layout
+    }
+}
+
+impl Optimizer {
+    /// Starts tracking the free rectangles of a layout, replaying its
+    /// existing placements once.
+    pub(super) fn pack_panel(&self, layout: PanelLayout) -> PackedPanel {
+        let free_rects = self.replay_free_rects(&layout);
+        PackedPanel { layout, free_rects }
+    }
+
+    /// Starts tracking the free rectangles of several layouts.
+    pub(super) fn pack_panels(&self, layouts: Vec<PanelLayout>) -> Vec<PackedPanel> {
+        layouts.into_iter().map(|l| self.pack_panel(l)).collect()
+    }
+
+    /// Adds a placement to a panel and splits only the free rectangles it touches.
+    pub(super) fn place(&self, panel: &mut PackedPanel, placement: Placement) {
+        let free_rects = std::mem::take(&mut panel.free_rects);
+        panel.free_rects = self.split_free_rects(free_rects, &placement);
+        panel.layout.placements.push(placement);
+    }
+
+    /// Removes a placement from a panel and returns it.
+    ///
+    /// Maximal free rectangles cannot be merged back locally, so the free list
+    /// is rebuilt from the remaining placements.
+    pub(super) fn unplace(&self, panel: &mut PackedPanel, index: usize) -> Placement {
+        let placement = panel.layout.placements.remove(index);
+        panel.free_rects = self.replay_free_rects(&panel.layout);
+        placement
+    }
+
+    /// Converts tracked panels back into plain layouts.
+    pub(super) fn unpack_panels(&self, panels: Vec<PackedPanel>) -> Vec<PanelLayout> {
+        panels.into_iter().map(PackedPanel::into_layout).collect()
+    }
+}
@@ -0,0 +1,24 @@
+/// Applies `f` to every element on the rayon thread pool and returns the
+/// results in input order. Callers pick a winner by scanning the results front
+/// to back, as the sequential loop does, so the chosen layout never depends on
+/// thread timing.
+#[cfg(feature = "parallel")]
+pub(super) fn map_ordered<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    use rayon::prelude::*;
+
+    items.par_iter().map(f).collect()
+}
+
+/// Applies `f` to every element and returns the results in input order.
+#[cfg(not(feature = "parallel"))]
+pub(super) fn map_ordered<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    F: Fn(&T) -> R,
+{
+    items.iter().map(f).collect()
+}
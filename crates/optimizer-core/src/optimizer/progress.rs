@@ -0,0 +1,143 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+
+/// Cooperative cancellation flag for `Optimizer::optimize_with_progress`.
+///
+/// Clones share the same flag, so one clone can be handed to the optimizer
+/// and another kept to call `cancel` from a different thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the optimizer to stop and return the best solution found so far.
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// Progress callback, cancellation flag and best solution of one material
+/// group, passed through the optimization phases.
+pub(super) struct Monitor<'a> {
+    on_progress: Option<&'a (dyn Fn(&Progress) + Sync)>,
+    cancel: Option<&'a CancelToken>,
+    material: Option<String>,
+    state: Mutex<MonitorState>,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    phase: Option<Phase>,
+    /// Best complete solution so far, as packed (before finalizing)
+    best: Option<Vec<PanelLayout>>,
+    best_panels: Option<u32>,
+    best_waste_percentage: Option<f64>,
+}
+
+impl<'a> Monitor<'a> {
+    pub fn new(on_progress: &'a (dyn Fn(&Progress) + Sync), cancel: &'a CancelToken) -> Self {
+        Self {
+            on_progress: Some(on_progress),
+            cancel: Some(cancel),
+            material: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Monitor that reports nothing and is never cancelled.
+    pub fn silent() -> Self {
+        Self {
+            on_progress: None,
+            cancel: None,
+            material: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Monitor for packing one material group, starting without a solution.
+    pub fn for_material(&self, material: Option<String>) -> Monitor<'a> {
+        Monitor {
+            on_progress: self.on_progress,
+            cancel: self.cancel,
+            material,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(CancelToken::is_cancelled)
+    }
+
+    pub fn report(&self, phase: Phase) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            state.phase = Some(phase.clone());
+            Progress {
+                phase,
+                material: self.material.clone(),
+                best_panels: state.best_panels,
+                best_waste_percentage: state.best_waste_percentage,
+                layouts: Vec::new(),
+            }
+        };
+        on_progress(&progress);
+    }
+}
+
+impl Optimizer {
+    /// Offers a complete solution to the monitor. When it beats the best one
+    /// so far, a report with its finalized layouts is sent right away.
+    pub(super) fn record_solution(
+        &self,
+        monitor: &Monitor,
+        layouts: &[PanelLayout],
+        expanded_items: &[Item],
+    ) {
+        let Some(on_progress) = monitor.on_progress else {
+            return;
+        };
+
+        let progress = {
+            let mut state = monitor.state.lock().unwrap();
+            if let Some(best) = &state.best {
+                if self.compare_layouts(layouts, best) != Ordering::Less {
+                    return;
+                }
+            }
+            state.best = Some(layouts.to_vec());
+
+            let mut preview = layouts.to_vec();
+            self.finalize_placements(&mut preview, expanded_items);
+            for layout in &mut preview {
+                layout.unused_areas = self.compute_output_unused_areas(layout);
+            }
+            let summary = self.calculate_summary(&preview);
+            state.best_panels = Some(summary.total_panels);
+            state.best_waste_percentage = Some(summary.waste_percentage);
+
+            let Some(phase) = state.phase.clone() else {
+                return;
+            };
+            Progress {
+                phase,
+                material: monitor.material.clone(),
+                best_panels: state.best_panels,
+                best_waste_percentage: state.best_waste_percentage,
+                layouts: preview,
+            }
+        };
+        on_progress(&progress);
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+use std::time::{Duration, Instant};
+
+/// Starting temperature of the annealing schedule, in energy units (one panel).
+const START_TEMPERATURE: f64 = 0.5;
+
+/// Temperature reached when the budget is used up.
+const END_TEMPERATURE: f64 = 0.01;
+
+impl Optimizer {
+    /// Improves a heuristic solution with simulated annealing over the item order.
+    ///
+    /// Each iteration perturbs the current order (swap two items, move one item,
+    /// reverse a segment or turn a rotatable item) and repacks it with
+    /// `pack_order`, so panel choice follows from the order. Worse orders are
+    /// accepted with a probability that shrinks as the budget runs out. The best
+    /// solution seen is returned, which is never worse than `best`. Cancelling
+    /// ends the search like an exhausted budget.
+    pub(super) fn search(
+        &self,
+        options: &SearchOptions,
+        start: &[Item],
+        best: Vec<PanelLayout>,
+        expanded_items: &[Item],
+        monitor: &Monitor,
+    ) -> Vec<PanelLayout> {
+        if start.len() < 2 {
+            return best;
+        }
+
+        let started = Instant::now();
+        let time_limit = options.time_limit_ms.map(Duration::from_millis);
+        let mut rng = SplitMix64::new(options.seed);
+
+        let mut current = start.to_vec();
+        let mut current_energy = self.search_energy(&best);
+        let mut best = best;
+        let mut iteration: u64 = 0;
+        monitor.report(Phase::Search { iteration });
+
+        loop {
+            // Fraction of the budget used so far (the larger of time and iterations)
+            let time_used = time_limit.map(|limit| {
+                started.elapsed().as_secs_f64() / limit.as_secs_f64().max(f64::EPSILON)
+            });
+            let iterations_used = options
+                .max_iterations
+                .map(|max| iteration as f64 / max.max(1) as f64);
+            let progress = time_used
+                .into_iter()
+                .chain(iterations_used)
+                .fold(0.0, f64::max);
+            if progress >= 1.0 || monitor.is_cancelled() {
+                break;
+            }
+            iteration += 1;
+
+            let mut candidate = current.clone();
+            self.perturb_order(&mut candidate, &mut rng);
+
+            let Ok(layouts) = self.pack_order(&candidate, expanded_items) else {
+                continue;
+            };
+            if !self.is_cuttable(&layouts) {
+                continue;
+            }
+
+            let energy = self.search_energy(&layouts);
+            let temperature =
+                START_TEMPERATURE * (END_TEMPERATURE / START_TEMPERATURE).powf(progress);
+            let accept = energy <= current_energy
+                || rng.next_f64() < (-(energy - current_energy) / temperature).exp();
+
+            if self.compare_layouts(&layouts, &best) == Ordering::Less {
+                monitor.report(Phase::Search { iteration });
+                self.record_solution(monitor, &layouts, expanded_items);
+                best = layouts;
+            }
+
+            if accept {
+                current = candidate;
+                current_energy = energy;
+            }
+        }
+
+        best
+    }
+
+    /// Applies one random move to an item order.
+    fn perturb_order(&self, order: &mut [Item], rng: &mut SplitMix64) {
+        let len = order.len();
+        let a = rng.next_index(len);
+        let b = rng.next_index(len);
+
+        match rng.next_index(4) {
+            0 => order.swap(a, b),
+            1 => {
+                // Move one item to another position
+                if a < b {
+                    order[a..=b].rotate_left(1);
+                } else {
+                    order[b..=a].rotate_right(1);
+                }
+            }
+            2 => order[a.min(b)..=a.max(b)].reverse(),
+            _ => {
+                let item = &mut order[a];
+                if item.can_rotate {
+                    std::mem::swap(&mut item.width, &mut item.height);
+                    item.grain = item.grain.rotated();
+                }
+            }
+        }
+    }
+
+    /// Scalar score of a solution for the annealing acceptance test (lower is better).
+    /// The objective counts whole units (panels, or cost in multiples of the
+    /// cheapest sheet) and the fill ratio of the least-used panel is added as a
+    /// fraction, so moves that empty that panel are rewarded before it disappears.
+    fn search_energy(&self, layouts: &[PanelLayout]) -> f64 {
+        let primary = match self.request.objective {
+            Objective::PanelCount => self.new_panels(layouts).count() as f64,
+            Objective::Cost => {
+                let cheapest = self
+                    .request
+                    .panel_types
+                    .iter()
+                    .map(|p| self.panel_cost(p))
+                    .filter(|&c| c > 0.0)
+                    .fold(f64::INFINITY, f64::min);
+                if cheapest.is_finite() {
+                    self.layouts_cost(layouts) / cheapest
+                } else {
+                    self.new_panels(layouts).count() as f64
+                }
+            }
+        };
+
+        let least_fill = self
+            .new_panels(layouts)
+            .map(|l| {
+                let used: f64 = l.placements.iter().map(|p| p.width * p.height).sum();
+                used / (l.width * l.height).max(1.0)
+            })
+            .fold(1.0, f64::min);
+
+        primary + least_fill * 0.9
+    }
+}
+
+/// Small deterministic random generator (SplitMix64), so results only depend on the seed.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform index in [0, len).
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
@@ -1,20 +1,20 @@
 use super::*;
-use crate::types::UnusedArea as OutputUnusedArea;
+use crate::remnants::select_reusable_areas;
 use std::collections::HashMap;
 
 impl Optimizer {
-    /// Aggregates how many panels of each type were consumed.
+    /// Aggregates how many new panels of each type were consumed.
     pub(super) fn count_panels(&self, layouts: &[PanelLayout]) -> HashMap<String, u32> {
         let mut counts = HashMap::new();
-        for layout in layouts {
+        for layout in self.new_panels(layouts) {
             *counts.entry(layout.panel_type_id.clone()).or_insert(0) += 1;
         }
         counts
     }
 
-    /// Computes utilization, waste, and optional remnant statistics.
+    /// Computes utilization, waste, cost, banding, and optional remnant statistics.
     pub(super) fn calculate_summary(&self, layouts: &[PanelLayout]) -> Summary {
-        let total_panels = layouts.len() as u32;
+        let total_panels = self.new_panels(layouts).count() as u32;
         let total_area: f64 = layouts.iter().map(|l| l.width * l.height).sum();
         let used_area: f64 = layouts
             .iter()
@@ -33,35 +33,13 @@ impl Optimizer {
                 let mut reusable_area = 0.0;
 
                 for layout in layouts {
-                    // compute_output_unused_areas can still contain overlaps. Select a
-                    // non-overlapping set (largest first) to avoid double counting.
-                    let mut unused_areas = self.compute_output_unused_areas(layout);
-                    unused_areas.sort_by(|a, b| {
-                        let area_a = a.width * a.height;
-                        let area_b = b.width * b.height;
-                        area_b
-                            .partial_cmp(&area_a)
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    });
-
-                    let mut accepted: Vec<OutputUnusedArea> = Vec::new();
-
-                    for area in unused_areas {
-                        let area_size = area.width * area.height;
-                        if area_size < min_size {
-                            continue;
-                        }
-
-                        if accepted
-                            .iter()
-                            .any(|existing| areas_overlap(existing, &area))
-                        {
-                            continue;
-                        }
-
-                        reusable_area += area_size;
-                        accepted.push(area);
-                    }
+                    // compute_output_unused_areas can still contain overlaps, so only a
+                    // non-overlapping selection counts to avoid double counting.
+                    let unused_areas = self.compute_output_unused_areas(layout);
+                    reusable_area += select_reusable_areas(unused_areas, min_size)
+                        .iter()
+                        .map(|a| a.width * a.height)
+                        .sum::<f64>();
                 }
 
                 // Clamp reusable_area to not exceed waste_area to avoid negative actual_waste
@@ -83,6 +61,49 @@ impl Optimizer {
                 (None, None, None)
             };
 
+        let mut cost_by_panel_type: HashMap<String, f64> = HashMap::new();
+        for layout in self.new_panels(layouts) {
+            let cost = self
+                .request
+                .panel_types
+                .iter()
+                .find(|p| p.id == layout.panel_type_id)
+                .and_then(|p| p.cost);
+            if let Some(cost) = cost {
+                *cost_by_panel_type
+                    .entry(layout.panel_type_id.clone())
+                    .or_insert(0.0) += cost;
+            }
+        }
+        let total_cost = if self.request.panel_types.iter().any(|p| p.cost.is_some()) {
+            Some(cost_by_panel_type.values().sum())
+        } else {
+            None
+        };
+
+        let mut banding_length: HashMap<String, f64> = HashMap::new();
+        for placement in layouts.iter().flat_map(|l| &l.placements) {
+            let Some(banding) = &placement.banding else {
+                continue;
+            };
+
+            // Bands cover the finished edge, i.e. the cut edge plus the bands at its ends
+            let finished_width = placement.width + banding.width_reduction();
+            let finished_height = placement.height + banding.height_reduction();
+            let edges = [
+                (&banding.bottom, finished_width),
+                (&banding.top, finished_width),
+                (&banding.left, finished_height),
+                (&banding.right, finished_height),
+            ];
+
+            for (band, length) in edges {
+                if let Some(band) = band {
+                    *banding_length.entry(band.material.clone()).or_insert(0.0) += length;
+                }
+            }
+        }
+
         Summary {
             total_panels,
             total_area,
@@ -92,14 +113,12 @@ impl Optimizer {
             reusable_remnant_area,
             actual_waste_area,
             actual_waste_percentage,
+            total_cost,
+            cost_by_panel_type,
+            banding_length,
+            lower_bound: None,
+            optimality_proven: None,
+            optimality_gap: None,
         }
     }
 }
-
-fn areas_overlap(a: &OutputUnusedArea, b: &OutputUnusedArea) -> bool {
-    let eps = 0.5;
-    a.x < b.x + b.width - eps
-        && a.x + a.width > b.x + eps
-        && a.y < b.y + b.height - eps
-        && a.y + a.height > b.y + eps
-}
@@ -10,6 +10,7 @@ fn test_simple_optimization() {
             height: 100.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![
             Item {
@@ -18,6 +19,7 @@ fn test_simple_optimization() {
                 height: 30.0,
                 quantity: 2,
                 can_rotate: true,
+                ..Default::default()
             },
             Item {
                 id: "item2".to_string(),
@@ -25,11 +27,13 @@ fn test_simple_optimization() {
                 height: 50.0,
                 quantity: 1,
                 can_rotate: false,
+                ..Default::default()
             },
         ],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -51,6 +55,7 @@ fn test_unused_areas_in_output() {
             height: 1000.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "item1".to_string(),
@@ -58,10 +63,12 @@ fn test_unused_areas_in_output() {
             height: 300.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -100,6 +107,7 @@ fn test_reusable_remnant_size() {
             height: 1000.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "item1".to_string(),
@@ -107,10 +115,12 @@ fn test_reusable_remnant_size() {
             height: 200.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: Some(10000.0),
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -138,6 +148,7 @@ fn test_optimize_for_reusable_remnants() {
             height: 1000.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "item1".to_string(),
@@ -145,10 +156,12 @@ fn test_optimize_for_reusable_remnants() {
             height: 300.0,
             quantity: 2,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: true,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -167,6 +180,7 @@ fn test_min_initial_usage_packs_single_panel() {
             height: 1200.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "shelf".to_string(),
@@ -174,10 +188,12 @@ fn test_min_initial_usage_packs_single_panel() {
             height: 300.0,
             quantity: 8,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: true,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -198,6 +214,7 @@ fn test_unused_area_allows_additional_row() {
             height: 1200.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "dummy".into(),
@@ -205,10 +222,12 @@ fn test_unused_area_allows_additional_row() {
             height: 100.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: true,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -227,6 +246,7 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf2".into(),
@@ -235,6 +255,7 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf3".into(),
@@ -243,6 +264,7 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf4".into(),
@@ -251,6 +273,7 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf5".into(),
@@ -259,6 +282,7 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf6".into(),
@@ -267,9 +291,12 @@ fn test_unused_area_allows_additional_row() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
         ],
         unused_areas: Vec::new(),
+        cuts: Vec::new(),
+        offcut_id: None,
     };
 
     let areas = optimizer.find_unused_areas(&layout);
@@ -286,6 +313,7 @@ fn test_try_place_item_after_vertical_piece() {
             height: 1200.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "shelf".into(),
@@ -293,10 +321,12 @@ fn test_try_place_item_after_vertical_piece() {
             height: 300.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: true,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -315,6 +345,7 @@ fn test_try_place_item_after_vertical_piece() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf2".into(),
@@ -323,6 +354,7 @@ fn test_try_place_item_after_vertical_piece() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf3".into(),
@@ -331,6 +363,7 @@ fn test_try_place_item_after_vertical_piece() {
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf4".into(),
@@ -339,44 +372,52 @@ fn test_try_place_item_after_vertical_piece() {
                 width: 300.0,
                 height: 600.0,
                 rotated: true,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf5".into(),
                 x: 0.0,
-                y: 602.0,
+                y: 604.0,
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf6".into(),
                 x: 602.0,
-                y: 602.0,
+                y: 604.0,
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
             Placement {
                 item_id: "shelf7".into(),
                 x: 1204.0,
-                y: 602.0,
+                y: 604.0,
                 width: 600.0,
                 height: 300.0,
                 rotated: false,
+                ..Default::default()
             },
         ],
         unused_areas: Vec::new(),
+        cuts: Vec::new(),
+        offcut_id: None,
     };
 
+    // The 304 mm gap between the rows holds a 300 mm shelf and a cut on each side
     let next_item = Item {
         id: "shelf8".into(),
         width: 600.0,
         height: 300.0,
         quantity: 1,
         can_rotate: false,
+        ..Default::default()
     };
 
-    let placement = optimizer.try_place_item(&next_item, &layout);
+    let placement = optimizer.try_place_item(&next_item, &optimizer.pack_panel(layout));
     assert!(placement.is_some());
 }
 
@@ -390,6 +431,7 @@ fn test_trimming_offsets_placements() {
             height: 400.0,
             trimming: 10.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "panel".into(),
@@ -397,10 +439,12 @@ fn test_trimming_offsets_placements() {
             height: 100.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -423,6 +467,7 @@ fn test_rotated_panel_allows_item_fit() {
             height: 2000.0,
             trimming: 0.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "wide_item".into(),
@@ -430,10 +475,12 @@ fn test_rotated_panel_allows_item_fit() {
             height: 900.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
     };
 
     let optimizer = Optimizer::new(request).unwrap();
@@ -460,6 +507,7 @@ fn test_invalid_trimming_rejected() {
             height: 20.0,
             trimming: 15.0,
             optional_items: vec![],
+            ..Default::default()
         }],
         items: vec![Item {
             id: "piece".into(),
@@ -467,12 +515,1933 @@ fn test_invalid_trimming_rejected() {
             height: 5.0,
             quantity: 1,
             can_rotate: false,
+            ..Default::default()
         }],
         min_initial_usage: false,
         min_reusable_remnant_size: None,
         optimize_for_reusable_remnants: false,
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request);
+    match result {
+        Err(OptimizerError::InvalidInput(errors)) => {
+            assert_eq!(errors[0].field, "panel_types[0].trimming")
+        }
+        _ => panic!("expected InvalidInput"),
+    }
+}
+
+#[test]
+fn test_cost_objective_prefers_cheaper_panels() {
+    let mut request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![
+            PanelType {
+                id: "large".into(),
+                width: 1000.0,
+                height: 1000.0,
+                cost: Some(100.0),
+                ..Default::default()
+            },
+            PanelType {
+                id: "small".into(),
+                width: 500.0,
+                height: 500.0,
+                cost: Some(10.0),
+                ..Default::default()
+            },
+        ],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 400.0,
+            quantity: 1,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+    assert_eq!(result.panels_required.get("large"), Some(&1));
+    assert_eq!(result.summary.total_cost, Some(100.0));
+
+    request.objective = Objective::Cost;
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    assert_eq!(result.panels_required.get("small"), Some(&1));
+    assert_eq!(result.summary.total_cost, Some(10.0));
+    assert_eq!(result.summary.cost_by_panel_type.get("small"), Some(&10.0));
+}
+
+#[test]
+fn test_cost_objective_requires_panel_costs() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "unpriced".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "piece".into(),
+            width: 100.0,
+            height: 100.0,
+            quantity: 1,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        objective: Objective::Cost,
+        ..Default::default()
     };
 
     let result = Optimizer::new(request);
     assert!(matches!(result, Err(OptimizerError::InvalidInput(_))));
 }
+
+#[test]
+fn test_stock_limit_falls_back_to_other_panel_type() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![
+            PanelType {
+                id: "oak".into(),
+                width: 500.0,
+                height: 500.0,
+                available: Some(1),
+                ..Default::default()
+            },
+            PanelType {
+                id: "mdf".into(),
+                width: 500.0,
+                height: 500.0,
+                ..Default::default()
+            },
+        ],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 400.0,
+            quantity: 3,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    assert_eq!(result.panels_required.get("oak"), Some(&1));
+    assert_eq!(result.panels_required.get("mdf"), Some(&2));
+}
+
+#[test]
+fn test_insufficient_stock_reports_unplaced_items() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "oak".into(),
+            width: 500.0,
+            height: 500.0,
+            available: Some(2),
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 400.0,
+            quantity: 3,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize();
+    match result {
+        Err(OptimizerError::InsufficientStock { unplaced_items }) => {
+            assert_eq!(unplaced_items.len(), 1);
+        }
+        other => panic!("expected InsufficientStock, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_guillotine_mode_produces_cut_tree() {
+    let content = include_str!("../../../../examples/complex.json");
+    let mut request: OptimizationRequest = serde_json::from_str(content).unwrap();
+    request.guillotine = true;
+
+    let optimizer = Optimizer::new(request).unwrap();
+    let result = optimizer.optimize().unwrap();
+
+    for layout in &result.layouts {
+        let cuts = optimizer.compute_guillotine_cuts(layout);
+        assert!(
+            cuts.is_some(),
+            "panel {} is not guillotine",
+            layout.panel_number
+        );
+        assert!(!layout.cuts.is_empty());
+        assert!(layout.cuts.iter().all(|c| c.stage >= 1));
+        assert_eq!(layout.cuts[0].stage, 1);
+    }
+}
+
+#[test]
+fn test_pinwheel_layout_is_not_guillotine() {
+    let request = OptimizationRequest {
+        cut_width: 0.0,
+        panel_types: vec![PanelType {
+            id: "square".into(),
+            width: 300.0,
+            height: 300.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "piece".into(),
+            width: 200.0,
+            height: 100.0,
+            quantity: 4,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        guillotine: true,
+        ..Default::default()
+    };
+    let optimizer = Optimizer::new(request).unwrap();
+
+    let placement = |id: &str, x: f64, y: f64, width: f64, height: f64| Placement {
+        item_id: id.into(),
+        x,
+        y,
+        width,
+        height,
+        rotated: width < height,
+        ..Default::default()
+    };
+    let layout = PanelLayout {
+        panel_type_id: "square".into(),
+        panel_number: 1,
+        width: 300.0,
+        height: 300.0,
+        trimming: 0.0,
+        placements: vec![
+            placement("a", 0.0, 0.0, 200.0, 100.0),
+            placement("b", 200.0, 0.0, 100.0, 200.0),
+            placement("c", 100.0, 200.0, 200.0, 100.0),
+            placement("d", 0.0, 100.0, 100.0, 200.0),
+        ],
+        unused_areas: Vec::new(),
+        cuts: Vec::new(),
+        offcut_id: None,
+    };
+
+    assert!(optimizer.compute_guillotine_cuts(&layout).is_none());
+}
+
+#[test]
+fn test_grain_panel_is_not_turned() {
+    let request = OptimizationRequest {
+        cut_width: 2.0,
+        panel_types: vec![PanelType {
+            id: "veneer".into(),
+            width: 1000.0,
+            height: 2000.0,
+            grain: Grain::AlongHeight,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "wide_item".into(),
+            width: 1500.0,
+            height: 900.0,
+            quantity: 1,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request);
+    match result {
+        Err(OptimizerError::CannotFitAll {
+            items,
+            largest_panel,
+        }) => {
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].id, "wide_item");
+            assert_eq!(largest_panel.unwrap().panel_type_id, "veneer");
+        }
+        other => panic!("expected CannotFitAll, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_grain_forces_matching_rotation() {
+    let request = OptimizationRequest {
+        cut_width: 2.0,
+        panel_types: vec![PanelType {
+            id: "oak".into(),
+            width: 1000.0,
+            height: 1000.0,
+            grain: Grain::AlongWidth,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "stile".into(),
+            width: 600.0,
+            height: 300.0,
+            quantity: 2,
+            can_rotate: true,
+            grain: Grain::AlongHeight,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    for placement in result.layouts.iter().flat_map(|l| &l.placements) {
+        assert!(placement.rotated);
+        assert!((placement.width - 300.0).abs() < f64::EPSILON);
+        assert!((placement.height - 600.0).abs() < f64::EPSILON);
+        assert_eq!(placement.grain, Grain::AlongHeight);
+    }
+}
+
+#[test]
+fn test_edge_banding_shrinks_cut_size_and_reports_length() {
+    let band = |material: &str| {
+        Some(EdgeBand {
+            thickness: 2.0,
+            material: material.into(),
+        })
+    };
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "chipboard".into(),
+            width: 1000.0,
+            height: 1000.0,
+            grain: Grain::AlongWidth,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "side".into(),
+            width: 300.0,
+            height: 600.0,
+            quantity: 2,
+            can_rotate: true,
+            grain: Grain::AlongWidth,
+            banding: Some(EdgeBanding {
+                top: band("abs_white"),
+                left: band("abs_oak"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    for placement in result.layouts.iter().flat_map(|l| &l.placements) {
+        assert!(!placement.rotated);
+        assert!((placement.width - 298.0).abs() < f64::EPSILON);
+        assert!((placement.height - 598.0).abs() < f64::EPSILON);
+        let banding = placement.banding.as_ref().unwrap();
+        assert_eq!(banding.top, band("abs_white"));
+        assert_eq!(banding.left, band("abs_oak"));
+    }
+
+    let lengths = &result.summary.banding_length;
+    assert!((lengths["abs_white"] - 600.0).abs() < 1e-6);
+    assert!((lengths["abs_oak"] - 1200.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_edge_banding_follows_rotation() {
+    let band = Some(EdgeBand {
+        thickness: 1.0,
+        material: "pvc".into(),
+    });
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "narrow".into(),
+            width: 700.0,
+            height: 400.0,
+            grain: Grain::AlongWidth,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "rail".into(),
+            width: 300.0,
+            height: 600.0,
+            quantity: 1,
+            can_rotate: true,
+            banding: Some(EdgeBanding {
+                left: band.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    let placement = &result.layouts[0].placements[0];
+    assert!(placement.rotated);
+    assert!((placement.width - 600.0).abs() < f64::EPSILON);
+    assert!((placement.height - 299.0).abs() < f64::EPSILON);
+    let banding = placement.banding.as_ref().unwrap();
+    assert_eq!(banding.bottom, band);
+    assert!(banding.left.is_none());
+}
+
+#[test]
+fn test_offcuts_are_consumed_before_new_panels() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "mdf".into(),
+            width: 2000.0,
+            height: 1000.0,
+            trimming: 10.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 300.0,
+            quantity: 2,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        offcuts: vec![
+            Offcut {
+                id: "low".into(),
+                width: 900.0,
+                height: 400.0,
+                panel_type_id: "mdf".into(),
+                priority: 0,
+            },
+            Offcut {
+                id: "high".into(),
+                width: 850.0,
+                height: 350.0,
+                panel_type_id: "mdf".into(),
+                priority: 5,
+            },
+        ],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    assert_eq!(result.offcuts_used, vec!["high".to_string()]);
+    assert_eq!(result.layouts.len(), 1);
+    assert_eq!(result.layouts[0].offcut_id.as_deref(), Some("high"));
+    assert_eq!(result.layouts[0].placements.len(), 2);
+    assert!(result.panels_required.is_empty());
+    assert_eq!(result.summary.total_panels, 0);
+}
+
+#[test]
+fn test_offcut_with_unknown_panel_type_is_rejected() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "mdf".into(),
+            width: 2000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 300.0,
+            quantity: 1,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        offcuts: vec![Offcut {
+            id: "scrap".into(),
+            width: 500.0,
+            height: 500.0,
+            panel_type_id: "oak".into(),
+            priority: 0,
+        }],
+        ..Default::default()
+    };
+
+    let Err(OptimizerError::InvalidInput(errors)) = Optimizer::new(request) else {
+        panic!("expected InvalidInput");
+    };
+    assert_eq!(errors[0].field, "offcuts[0].panel_type_id");
+
+    // Requests from before the rename still name the panel type `material`
+    let offcut: Offcut =
+        serde_json::from_str(r#"{"id": "scrap", "width": 500, "height": 500, "material": "mdf"}"#)
+            .unwrap();
+    assert_eq!(offcut.panel_type_id, "mdf");
+}
+
+#[test]
+fn test_remnant_inventory_lists_non_overlapping_remnants() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "ply".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "part".into(),
+            width: 400.0,
+            height: 400.0,
+            quantity: 1,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        min_reusable_remnant_size: Some(100_000.0),
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    let remnants = crate::remnant_inventory(&result, 100_000.0);
+
+    let total: f64 = remnants.iter().map(|r| r.width * r.height).sum();
+    let reusable = result.summary.reusable_remnant_area.unwrap();
+    assert!((total - reusable).abs() < 1e-6);
+    assert_eq!(remnants[0].id, "ply-1-R1");
+    assert!(remnants
+        .iter()
+        .all(|r| r.panel_type_id == "ply" && !r.trimmed));
+
+    let csv = crate::remnants_to_csv(&remnants).unwrap();
+    assert!(csv.starts_with("id,panel_type_id,panel_number,offcut_id,x,y,width,height,trimmed"));
+    assert_eq!(csv.lines().count(), remnants.len() + 1);
+}
+
+#[test]
+fn test_materials_are_packed_separately() {
+    let panel = |id: &str, material: &str| PanelType {
+        id: id.into(),
+        width: 2000.0,
+        height: 1000.0,
+        material: Some(material.into()),
+        ..Default::default()
+    };
+    let item = |id: &str, material: &str| Item {
+        id: id.into(),
+        width: 600.0,
+        height: 400.0,
+        quantity: 2,
+        can_rotate: true,
+        material: Some(material.into()),
+        ..Default::default()
+    };
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![panel("carcass", "mdf_18"), panel("back", "hdf_8")],
+        items: vec![item("side", "mdf_18"), item("back", "hdf_8")],
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+
+    assert_eq!(result.panels_required.get("carcass"), Some(&1));
+    assert_eq!(result.panels_required.get("back"), Some(&1));
+    for layout in &result.layouts {
+        let expected_prefix = if layout.panel_type_id == "carcass" {
+            "side"
+        } else {
+            "back"
+        };
+        assert!(layout
+            .placements
+            .iter()
+            .all(|p| p.item_id.starts_with(expected_prefix)));
+    }
+
+    assert_eq!(result.materials.len(), 2);
+    assert_eq!(result.materials[0].material.as_deref(), Some("mdf_18"));
+    assert_eq!(result.materials[0].summary.total_panels, 1);
+    assert_eq!(result.summary.total_panels, 2);
+
+    let mut unknown = request;
+    unknown.items[1].material = Some("oak_19".into());
+    assert!(matches!(
+        Optimizer::new(unknown),
+        Err(OptimizerError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_oversized_item_reports_largest_panel_of_its_material() {
+    let panel = |id: &str, width: f64, material: &str| PanelType {
+        id: id.into(),
+        width,
+        height: 1000.0,
+        material: Some(material.into()),
+        ..Default::default()
+    };
+    // Too long for the mdf panel, though the hdf panel would hold it
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![
+            panel("carcass", 2000.0, "mdf_18"),
+            panel("back", 3000.0, "hdf_8"),
+        ],
+        items: vec![Item {
+            id: "side".into(),
+            width: 2500.0,
+            height: 600.0,
+            quantity: 1,
+            can_rotate: true,
+            material: Some("mdf_18".into()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    match Optimizer::new(request) {
+        Err(OptimizerError::CannotFitAll {
+            items,
+            largest_panel,
+        }) => {
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].id, "side");
+            assert_eq!(largest_panel.unwrap().panel_type_id, "carcass");
+        }
+        other => panic!("expected CannotFitAll, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_search_is_reproducible_and_never_worse() {
+    let mut request = OptimizationRequest {
+        cut_width: 4.0,
+        panel_types: vec![PanelType {
+            id: "board".into(),
+            width: 1200.0,
+            height: 800.0,
+            ..Default::default()
+        }],
+        items: vec![
+            Item {
+                id: "a".into(),
+                width: 530.0,
+                height: 310.0,
+                quantity: 5,
+                can_rotate: true,
+                ..Default::default()
+            },
+            Item {
+                id: "b".into(),
+                width: 270.0,
+                height: 410.0,
+                quantity: 6,
+                can_rotate: true,
+                ..Default::default()
+            },
+            Item {
+                id: "c".into(),
+                width: 190.0,
+                height: 150.0,
+                quantity: 9,
+                can_rotate: false,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let heuristic = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+
+    request.search = Some(SearchOptions {
+        max_iterations: Some(150),
+        seed: 7,
+        ..Default::default()
+    });
+    let first = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+    let second = Optimizer::new(request).unwrap().optimize().unwrap();
+
+    assert!(first.summary.total_panels <= heuristic.summary.total_panels);
+    assert!(first.summary.waste_area <= heuristic.summary.waste_area + 1e-6);
+    assert_eq!(
+        serde_json::to_string(&first.layouts).unwrap(),
+        serde_json::to_string(&second.layouts).unwrap()
+    );
+}
+
+#[test]
+fn test_exact_mode_proves_panel_count() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "big".into(),
+            width: 600.0,
+            height: 600.0,
+            quantity: 3,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        exact: true,
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    let summary = &result.summary;
+
+    // The area bound says 2 panels; the search rules that out
+    assert_eq!(summary.total_panels, 3);
+    assert_eq!(summary.lower_bound, Some(3));
+    assert_eq!(summary.optimality_proven, Some(true));
+    assert_eq!(summary.optimality_gap, Some(0));
+}
+
+#[test]
+fn test_exact_mode_proves_panel_count_with_many_small_items() {
+    let item = |id: &str, size: f64, quantity: u32| Item {
+        id: id.into(),
+        width: size,
+        height: size,
+        quantity,
+        can_rotate: true,
+        ..Default::default()
+    };
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![item("big", 600.0, 2), item("small", 100.0, 10)],
+        exact: true,
+        ..Default::default()
+    };
+
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    let summary = &result.summary;
+
+    // Placing the big parts first rules out one panel without trying every
+    // position of the small ones
+    assert_eq!(summary.total_panels, 2);
+    assert_eq!(summary.lower_bound, Some(2));
+    assert_eq!(summary.optimality_proven, Some(true));
+}
+
+#[test]
+fn test_repeated_runs_choose_identical_layouts() {
+    let items = (0..40)
+        .map(|i| Item {
+            id: format!("part_{}", i),
+            width: 150.0 + (i * 37 % 400) as f64,
+            height: 100.0 + (i * 53 % 300) as f64,
+            quantity: 3,
+            can_rotate: i % 3 != 0,
+            ..Default::default()
+        })
+        .collect();
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![
+            PanelType {
+                id: "large".into(),
+                width: 2800.0,
+                height: 2070.0,
+                ..Default::default()
+            },
+            PanelType {
+                id: "small".into(),
+                width: 1250.0,
+                height: 1000.0,
+                ..Default::default()
+            },
+        ],
+        items,
+        ..Default::default()
+    };
+
+    // With the `parallel` feature, thread timing must not change the result
+    let first = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+    for _ in 0..3 {
+        let again = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+        assert_eq!(
+            serde_json::to_string(&first.layouts).unwrap(),
+            serde_json::to_string(&again.layouts).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_packed_panel_tracks_same_free_areas_as_replay() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            trimming: 10.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "part".into(),
+            width: 500.0,
+            height: 300.0,
+            quantity: 9,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let optimizer = Optimizer::new(request).unwrap();
+    let layout = optimizer.optimize().unwrap().layouts.remove(0);
+
+    let mut panel = optimizer.pack_panel(PanelLayout {
+        placements: Vec::new(),
+        ..layout.clone()
+    });
+    for placement in layout.placements.clone() {
+        optimizer.place(&mut panel, placement);
+    }
+    let removed = optimizer.unplace(&mut panel, 0);
+    optimizer.place(&mut panel, removed);
+
+    let tracked: Vec<_> = panel
+        .unused_areas()
+        .map(|a| (a.x, a.y, a.width, a.height))
+        .collect();
+    let replayed: Vec<_> = optimizer
+        .find_unused_areas(panel.layout())
+        .iter()
+        .map(|a| (a.x, a.y, a.width, a.height))
+        .collect();
+    assert_eq!(tracked, replayed);
+}
+
+#[test]
+fn test_progress_reports_strategies_and_best_panel_count() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "part".into(),
+            width: 400.0,
+            height: 400.0,
+            quantity: 6,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let reports = std::sync::Mutex::new(Vec::new());
+    let result = Optimizer::new(request)
+        .unwrap()
+        .optimize_with_progress(
+            |progress| reports.lock().unwrap().push(progress.clone()),
+            &CancelToken::new(),
+        )
+        .unwrap();
+    let reports = reports.into_inner().unwrap();
+
+    assert!(!result.cancelled);
+    assert!(reports
+        .iter()
+        .any(|p| p.phase == Phase::Strategy { index: 1, total: 6 }));
+    assert!(reports
+        .iter()
+        .any(|p| p.phase == Phase::PanelReduction { index: 6, total: 6 }));
+    assert_eq!(
+        reports.last().unwrap().best_panels,
+        Some(result.summary.total_panels)
+    );
+
+    // The latest report carrying layouts shows the best solution in full
+    let best = reports
+        .iter()
+        .rev()
+        .find(|p| !p.layouts.is_empty())
+        .unwrap();
+    assert_eq!(best.layouts.len(), result.layouts.len());
+    assert_eq!(
+        best.layouts
+            .iter()
+            .map(|l| l.placements.len())
+            .sum::<usize>(),
+        6
+    );
+    assert_eq!(
+        best.best_waste_percentage,
+        Some(result.summary.waste_percentage)
+    );
+}
+
+#[test]
+fn test_cancelled_run_returns_complete_solution() {
+    let request = OptimizationRequest {
+        cut_width: 3.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 1000.0,
+            height: 1000.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "part".into(),
+            width: 300.0,
+            height: 200.0,
+            quantity: 20,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        search: Some(SearchOptions {
+            max_iterations: Some(1_000_000),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let result = Optimizer::new(request)
+        .unwrap()
+        .optimize_with_progress(|_| {}, &cancel)
+        .unwrap();
+
+    assert!(result.cancelled);
+    let placed: usize = result.layouts.iter().map(|l| l.placements.len()).sum();
+    assert_eq!(placed, 20);
+}
+
+#[test]
+fn test_validate_reports_every_problem() {
+    let request = OptimizationRequest {
+        cut_width: -1.0,
+        panel_types: vec![
+            PanelType {
+                id: "sheet".into(),
+                width: 1000.0,
+                height: 500.0,
+                ..Default::default()
+            },
+            PanelType {
+                id: "spare".into(),
+                width: 800.0,
+                height: 400.0,
+                material: Some("oak".into()),
+                ..Default::default()
+            },
+        ],
+        items: vec![
+            Item {
+                id: "shelf".into(),
+                width: f64::NAN,
+                height: 200.0,
+                quantity: 0,
+                ..Default::default()
+            },
+            Item {
+                id: "shelf".into(),
+                width: 300.0,
+                height: 200.0,
+                quantity: 1,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let report = validate(&request);
+    let fields: Vec<&str> = report.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        [
+            "cut_width",
+            "items[0].width",
+            "items[0].quantity",
+            "items[1].id"
+        ]
+    );
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].field, "panel_types[1].material");
+
+    match Optimizer::new(request) {
+        Err(OptimizerError::InvalidInput(errors)) => assert_eq!(errors, report.errors),
+        _ => panic!("expected InvalidInput"),
+    }
+}
+
+#[test]
+fn test_validate_rejects_items_larger_than_every_usable_panel() {
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 1000.0,
+            height: 500.0,
+            trimming: 10.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door".into(),
+            width: 490.0,
+            height: 990.0,
+            quantity: 1,
+            can_rotate: false,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let report = validate(&request);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].field, "items[0]");
+
+    // A slightly smaller door fits the usable 480x980 of the turned panel
+    let mut smaller = request.clone();
+    smaller.items[0].width = 470.0;
+    smaller.items[0].height = 970.0;
+    assert!(validate(&smaller).is_valid());
+}
+
+#[test]
+fn test_validate_rejects_items_whose_copy_ids_collide() {
+    let item = |id: &str, quantity: u32| Item {
+        id: id.into(),
+        width: 300.0,
+        height: 200.0,
+        quantity,
+        ..Default::default()
+    };
+    let mut request = shelves_request();
+    // The two copies of `shelf` are placed as `shelf_1` and `shelf_2`
+    request.items = vec![item("shelf", 2), item("shelf_1", 1)];
+
+    let report = validate(&request);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].field, "items[1].id");
+    assert!(report.errors[0].message.contains("'shelf_1'"));
+
+    request.items.reverse();
+    let report = validate(&request);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].field, "items[1].id");
+
+    request.items[0].id = "shelf_3".into();
+    assert!(validate(&request).is_valid());
+
+    // Ids that only look like copy ids are fine
+    request.items[0].id = "shelf_01".into();
+    assert!(validate(&request).is_valid());
+}
+
+#[test]
+fn test_validate_handles_huge_quantities() {
+    let mut request = shelves_request();
+    request.exact = true;
+    for item in &mut request.items {
+        item.quantity = u32::MAX;
+    }
+    request.items[2].id = format!("shelf_a_{}", u32::MAX);
+    request.items[2].quantity = 1;
+
+    let report = validate(&request);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].field, "items[2].id");
+    let exact = report.warnings.iter().find(|w| w.field == "exact").unwrap();
+    assert!(exact
+        .message
+        .contains(&(2 * u64::from(u32::MAX) + 1).to_string()));
+}
+
+fn shelves_request() -> OptimizationRequest {
+    OptimizationRequest {
+        cut_width: 5.0,
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            ..Default::default()
+        }],
+        items: vec![
+            Item {
+                id: "shelf_a".into(),
+                width: 600.0,
+                height: 300.0,
+                quantity: 4,
+                can_rotate: true,
+                ..Default::default()
+            },
+            Item {
+                id: "shelf_b".into(),
+                width: 800.0,
+                height: 400.0,
+                quantity: 2,
+                can_rotate: true,
+                ..Default::default()
+            },
+            Item {
+                id: "back".into(),
+                width: 1200.0,
+                height: 600.0,
+                quantity: 1,
+                can_rotate: false,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_optimized_result_passes_verification() {
+    let request = shelves_request();
+    let result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+
+    // Parts placed left of or below earlier ones keep the cut width to them
+    assert!(crate::verify(&request, &result).is_empty());
+}
+
+#[test]
+fn test_verify_reports_broken_result() {
+    let request = shelves_request();
+    let mut result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+
+    let placements = &mut result.layouts[0].placements;
+    let back = placements.iter().position(|p| p.item_id == "back").unwrap();
+    placements[back].rotated = true;
+    let removed = placements.pop().unwrap();
+    placements.push(Placement {
+        item_id: "shelf_a_9".into(),
+        x: placements[back].x,
+        y: placements[back].y,
+        ..removed
+    });
+
+    let kinds: Vec<ViolationKind> = crate::verify(&request, &result)
+        .into_iter()
+        .map(|v| v.kind)
+        .collect();
+    for kind in [
+        ViolationKind::WrongSize,
+        ViolationKind::RotationNotAllowed,
+        ViolationKind::UnknownItem,
+        ViolationKind::Overlap,
+        ViolationKind::MissingItem,
+    ] {
+        assert!(kinds.contains(&kind), "{:?} not in {:?}", kind, kinds);
+    }
+}
+
+#[test]
+fn test_verify_reports_optional_item_on_foreign_panel_type() {
+    let mut request = shelves_request();
+    let mut result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+    request.panel_types.push(PanelType {
+        id: "board".into(),
+        optional_items: vec![OptionalItem {
+            id: "filler".into(),
+            width: 100.0,
+            height: 100.0,
+            can_rotate: true,
+            priority: 0,
+            grain: Grain::None,
+        }],
+        ..request.panel_types[0].clone()
+    });
+
+    // The filler fits the leftover space of the sheet, but belongs to the board
+    let free = result.layouts[0].unused_areas[0].clone();
+    result.layouts[0].placements.push(Placement {
+        item_id: "filler".into(),
+        x: free.x,
+        y: free.y,
+        width: 100.0,
+        height: 100.0,
+        rotated: false,
+        ..Default::default()
+    });
+
+    let violations = crate::verify(&request, &result);
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert_eq!(violations[0].kind, ViolationKind::WrongPanelType);
+
+    request.panel_types[1].id = "sheet".into();
+    request.panel_types.swap(0, 1);
+    assert!(crate::verify(&request, &result).is_empty());
+}
+
+#[test]
+fn test_verify_reports_offcut_layout_of_another_panel_type() {
+    let panel = |id: &str| PanelType {
+        id: id.into(),
+        width: 2000.0,
+        height: 1000.0,
+        ..Default::default()
+    };
+    let request = OptimizationRequest {
+        panel_types: vec![panel("mdf"), panel("oak")],
+        items: vec![Item {
+            id: "door".into(),
+            width: 400.0,
+            height: 300.0,
+            quantity: 1,
+            can_rotate: true,
+            ..Default::default()
+        }],
+        offcuts: vec![Offcut {
+            id: "scrap".into(),
+            width: 500.0,
+            height: 500.0,
+            panel_type_id: "mdf".into(),
+            priority: 0,
+        }],
+        ..Default::default()
+    };
+    let mut result = Optimizer::new(request.clone()).unwrap().optimize().unwrap();
+    assert_eq!(result.layouts[0].offcut_id.as_deref(), Some("scrap"));
+    assert!(crate::verify(&request, &result).is_empty());
+
+    result.layouts[0].panel_type_id = "oak".into();
+    let violations = crate::verify(&request, &result);
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert_eq!(violations[0].kind, ViolationKind::WrongPanelType);
+}
+
+#[test]
+fn test_edit_rejects_overlapping_move() {
+    let optimizer = Optimizer::new(shelves_request()).unwrap();
+    let result = optimizer.optimize().unwrap();
+    let first = &result.layouts[0].placements[0];
+
+    let edit = LayoutEdit::Move {
+        target: PlacementRef {
+            layout: 0,
+            placement: 1,
+        },
+        x: first.x,
+        y: first.y,
+    };
+    match optimizer.apply_edit(&result, &edit) {
+        Err(OptimizerError::InvalidEdit(violations)) => {
+            assert!(violations.iter().any(|v| v.kind == ViolationKind::Overlap));
+        }
+        other => panic!("expected InvalidEdit, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_edit_remove_and_add_recompute_summary() {
+    let request = shelves_request();
+    let optimizer = Optimizer::new(request.clone()).unwrap();
+    let result = optimizer.optimize().unwrap();
+    let part = result.layouts[0].placements[0].clone();
+    let target = PlacementRef {
+        layout: 0,
+        placement: 0,
+    };
+
+    let removed = optimizer
+        .apply_edit(&result, &LayoutEdit::Remove { target })
+        .unwrap();
+    let freed = part.width * part.height;
+    assert!((result.summary.used_area - removed.summary.used_area - freed).abs() < 1e-6);
+    assert!(removed.layouts[0].unused_areas.iter().any(|a| {
+        a.x <= part.x && a.y <= part.y && a.width >= part.width && a.height >= part.height
+    }));
+    let kinds: Vec<ViolationKind> = crate::verify(&request, &removed)
+        .into_iter()
+        .map(|v| v.kind)
+        .collect();
+    assert_eq!(kinds, vec![ViolationKind::MissingItem]);
+
+    let edit = LayoutEdit::Add {
+        layout: 0,
+        item_id: part.item_id.clone(),
+        x: part.x,
+        y: part.y,
+        rotated: part.rotated,
+    };
+    let restored = optimizer.apply_edit(&removed, &edit).unwrap();
+    assert!((restored.summary.used_area - result.summary.used_area).abs() < 1e-6);
+    assert!(crate::verify(&request, &restored).is_empty());
+
+    // The same copy cannot be placed twice
+    assert!(matches!(
+        optimizer.apply_edit(&restored, &edit),
+        Err(OptimizerError::InvalidInput(_))
+    ));
+}
+
+/// Square 500x500 panel without kerf, holding the given items.
+fn edit_request(items: &[(&str, f64, f64, bool)]) -> OptimizationRequest {
+    OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 500.0,
+            height: 500.0,
+            ..Default::default()
+        }],
+        items: items
+            .iter()
+            .map(|&(id, width, height, can_rotate)| Item {
+                id: id.into(),
+                width,
+                height,
+                quantity: 1,
+                can_rotate,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn find_placement(result: &OptimizationResult, item_id: &str) -> PlacementRef {
+    for (layout, l) in result.layouts.iter().enumerate() {
+        if let Some(placement) = l.placements.iter().position(|p| p.item_id == item_id) {
+            return PlacementRef { layout, placement };
+        }
+    }
+    panic!("{} is not placed", item_id);
+}
+
+#[test]
+fn test_edit_rotate_respects_can_rotate() {
+    let request = edit_request(&[
+        ("fixed", 300.0, 100.0, false),
+        ("turnable", 200.0, 100.0, true),
+    ]);
+    let optimizer = Optimizer::new(request.clone()).unwrap();
+    let result = optimizer.optimize().unwrap();
+
+    let edit = LayoutEdit::Rotate {
+        target: find_placement(&result, "fixed"),
+    };
+    match optimizer.apply_edit(&result, &edit) {
+        Err(OptimizerError::InvalidEdit(violations)) => {
+            assert!(violations
+                .iter()
+                .any(|v| v.kind == ViolationKind::RotationNotAllowed));
+        }
+        other => panic!("expected InvalidEdit, got {:?}", other.map(|_| ())),
+    }
+
+    let target = find_placement(&result, "turnable");
+    let before = result.layouts[target.layout].placements[target.placement].clone();
+    let rotated = optimizer
+        .apply_edit(&result, &LayoutEdit::Rotate { target })
+        .unwrap();
+    let after = &rotated.layouts[0].placements[find_placement(&rotated, "turnable").placement];
+    assert_eq!(after.rotated, !before.rotated);
+    assert_eq!((after.x, after.y), (before.x, before.y));
+    assert_eq!((after.width, after.height), (before.height, before.width));
+    assert!(crate::verify(&request, &rotated).is_empty());
+}
+
+#[test]
+fn test_edit_swap_trades_parts_between_panels() {
+    // Too large to share a panel
+    let request = edit_request(&[
+        ("large", 450.0, 450.0, false),
+        ("medium", 300.0, 300.0, false),
+    ]);
+    let optimizer = Optimizer::new(request.clone()).unwrap();
+    let result = optimizer.optimize().unwrap();
+    assert_eq!(result.layouts.len(), 2);
+    let (large, medium) = (
+        find_placement(&result, "large"),
+        find_placement(&result, "medium"),
+    );
+    assert_ne!(large.layout, medium.layout);
+
+    let swapped = optimizer
+        .apply_edit(
+            &result,
+            &LayoutEdit::Swap {
+                first: large,
+                second: medium,
+            },
+        )
+        .unwrap();
+    assert_eq!(find_placement(&swapped, "large").layout, medium.layout);
+    assert_eq!(find_placement(&swapped, "medium").layout, large.layout);
+    assert!(crate::verify(&request, &swapped).is_empty());
+    assert!((swapped.summary.used_area - result.summary.used_area).abs() < 1e-6);
+
+    // The freed space is recomputed on both panels
+    let free_area = |result: &OptimizationResult, layout: usize| -> f64 {
+        result.layouts[layout]
+            .unused_areas
+            .iter()
+            .map(|a| a.width * a.height)
+            .sum()
+    };
+    assert!(free_area(&swapped, large.layout) > free_area(&result, large.layout));
+    assert!(free_area(&swapped, medium.layout) < free_area(&result, medium.layout));
+}
+
+#[test]
+fn test_edit_move_to_panel_drops_emptied_layout() {
+    let request = edit_request(&[
+        ("strip", 300.0, 500.0, false),
+        ("block", 100.0, 100.0, false),
+    ]);
+    let optimizer = Optimizer::new(request.clone()).unwrap();
+    let packed = optimizer.optimize().unwrap();
+    assert_eq!(packed.layouts.len(), 1);
+
+    // Spread the parts over two panels, the block alone on the first
+    let mut result = packed.clone();
+    let block = find_placement(&result, "block");
+    let placement = result.layouts[0].placements.remove(block.placement);
+    let mut first = result.layouts[0].clone();
+    first.placements = vec![Placement {
+        x: 0.0,
+        y: 0.0,
+        ..placement.clone()
+    }];
+    result.layouts.insert(0, first);
+    optimizer.renumber_panels(&mut result.layouts);
+    result.panels_required = optimizer.count_panels(&result.layouts);
+    result.summary = optimizer.calculate_summary(&result.layouts);
+    assert_eq!(result.panels_required.get("sheet"), Some(&2));
+    assert_eq!(result.layouts[1].panel_number, 2);
+
+    let edit = LayoutEdit::MoveToPanel {
+        target: PlacementRef {
+            layout: 0,
+            placement: 0,
+        },
+        layout: 1,
+        x: placement.x,
+        y: placement.y,
+    };
+    let merged = optimizer.apply_edit(&result, &edit).unwrap();
+    assert_eq!(merged.layouts.len(), 1);
+    assert_eq!(merged.layouts[0].panel_number, 1);
+    assert_eq!(merged.layouts[0].placements.len(), 2);
+    assert_eq!(merged.panels_required.get("sheet"), Some(&1));
+    assert_eq!(merged.summary.total_panels, 1);
+    assert!(crate::verify(&request, &merged).is_empty());
+}
+
+#[test]
+fn test_edit_adds_optional_items_only_to_their_panel_type() {
+    let mut request = edit_request(&[("strip", 300.0, 500.0, false)]);
+    request.panel_types.push(PanelType {
+        id: "board".into(),
+        optional_items: vec![OptionalItem {
+            id: "filler".into(),
+            width: 100.0,
+            height: 100.0,
+            can_rotate: true,
+            priority: 0,
+            grain: Grain::None,
+        }],
+        ..request.panel_types[0].clone()
+    });
+    // Keep the filler off the sheet while optimizing
+    request.panel_types[1].available = Some(0);
+    let optimizer = Optimizer::new(request.clone()).unwrap();
+    let result = optimizer.optimize().unwrap();
+    assert_eq!(result.layouts[0].panel_type_id, "sheet");
+
+    let edit = LayoutEdit::Add {
+        layout: 0,
+        item_id: "filler".into(),
+        x: 300.0,
+        y: 0.0,
+        rotated: false,
+    };
+    match optimizer.apply_edit(&result, &edit) {
+        Err(OptimizerError::InvalidEdit(violations)) => {
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].kind, ViolationKind::WrongPanelType);
+        }
+        other => panic!("expected InvalidEdit, got {:?}", other.map(|_| ())),
+    }
+
+    // The same filler belongs on a sheet once the sheet lists it
+    let mut own = request.clone();
+    own.panel_types[0].optional_items = own.panel_types.pop().unwrap().optional_items;
+    let optimizer = Optimizer::new(own.clone()).unwrap();
+    let added = optimizer.apply_edit(&result, &edit).unwrap();
+    assert_eq!(added.optional_items_used, vec!["filler".to_string()]);
+    assert!(crate::verify(&own, &added).is_empty());
+}
+
+#[test]
+fn test_svg_escapes_ids_and_colors_item_copies() {
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "oak & <ash>".into(),
+            width: 1000.0,
+            height: 500.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door\"s".into(),
+            width: 300.0,
+            height: 200.0,
+            quantity: 2,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    let options = SvgOptions {
+        item_colors: [("door\"s".to_string(), "#123456".to_string())].into(),
+        dimensions: true,
+        ..Default::default()
+    };
+
+    let svg = crate::render_svg(&result, &options).unwrap();
+    assert!(svg.contains("oak &amp; &lt;ash&gt; #1"));
+    assert!(svg.contains("door&quot;s_2"));
+    assert!(!svg.contains("<ash>"));
+    assert_eq!(svg.matches(r##"fill="#123456""##).count(), 3);
+    assert!(svg.contains("300 x 200"));
+
+    let panels = crate::render_panel_svgs(&result, &options).unwrap();
+    assert_eq!(panels.len(), result.layouts.len());
+}
+
+#[test]
+fn test_pdf_has_cover_and_page_per_panel() {
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            ..Default::default()
+        }],
+        items: vec![
+            Item {
+                id: "door".into(),
+                width: 2000.0,
+                height: 1000.0,
+                quantity: 2,
+                ..Default::default()
+            },
+            Item {
+                id: "pin".into(),
+                width: 40.0,
+                height: 40.0,
+                quantity: 120,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    assert_eq!(result.layouts.len(), 2);
+
+    let pdf = crate::render_pdf(&result, &PdfOptions::default());
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-"));
+    assert!(text.contains("(Panels required)"));
+    assert!(text.contains("(2000 x 1000)"));
+    // Part lists too long for the panel page continue on extra pages
+    let pages = text.matches("/Type /Page\n").count();
+    assert!(pages > 3);
+    assert!(text.contains("(continued)"));
+    assert!(text.contains(&format!("(Page {} of {})", pages, pages)));
+}
+
+#[test]
+fn test_dxf_keeps_placement_coordinates() {
+    let result = Optimizer::new(shelves_request())
+        .unwrap()
+        .optimize()
+        .unwrap();
+
+    let dxf = crate::render_dxf(&result, &DxfOptions::default());
+    for layer in ["PANEL", "PARTS", "LABELS", "UNUSED"] {
+        assert!(dxf.contains(&format!("  2\n{}\n", layer)));
+    }
+    assert_eq!(dxf.matches("\nINSERT\n").count(), result.layouts.len());
+    assert!(dxf.ends_with("  0\nEOF\n"));
+
+    let panels = crate::render_panel_dxfs(&result, &DxfOptions::default());
+    assert_eq!(panels.len(), result.layouts.len());
+    for (layout, panel) in result.layouts.iter().zip(&panels) {
+        for placement in &layout.placements {
+            let corner = format!(
+                "VERTEX\n  8\nPARTS\n 10\n{}\n 20\n{}\n",
+                placement.x, placement.y
+            );
+            assert!(panel.contains(&corner), "{}", corner);
+        }
+    }
+
+    let inches = DxfOptions {
+        units: DxfUnits::Inches,
+    };
+    let panel = &crate::render_panel_dxfs(&result, &inches)[0];
+    // R12 readers ignore `$INSUNITS`, so the coordinates carry the unit
+    let width = result.layouts[0].width / 25.4;
+    let height = result.layouts[0].height / 25.4;
+    assert!(panel.contains(&format!("$EXTMAX\n 10\n{}\n 20\n{}\n", width, height)));
+    assert!(panel.contains(&format!(" 10\n{}\n", width)));
+    assert!(panel.contains("$ACADVER\n  1\nAC1009\n"));
+    assert!(panel.contains("$INSUNITS\n 70\n1\n"));
+}
+
+#[test]
+fn test_import_cut_list_maps_columns() {
+    let csv = "Part;Length;Width;Qty;Turn;Material;Remark\n\
+               side;720;560;2;no;oak;cabinet A\n\
+               ;;;;;;\n\
+               shelf;764,5;540;;;oak;\n\
+               top;600;400;1;X;oak;\n";
+    let options = CutListOptions {
+        id_column: "part".into(),
+        quantity_column: "qty".into(),
+        rotation_column: "turn".into(),
+        notes_column: "remark".into(),
+        ..Default::default()
+    };
+
+    let items = crate::import_cut_list(csv.as_bytes(), &options).unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].id, "side");
+    assert_eq!((items[0].width, items[0].height), (720.0, 560.0));
+    assert_eq!(items[0].quantity, 2);
+    assert!(!items[0].can_rotate);
+    assert_eq!(items[0].material.as_deref(), Some("oak"));
+    assert_eq!(items[0].notes.as_deref(), Some("cabinet A"));
+    assert_eq!(items[1].width, 764.5);
+    assert_eq!(items[1].quantity, 1);
+    assert!(items[1].can_rotate);
+    assert_eq!(items[1].notes, None);
+    // A ticked cell allows rotation
+    assert!(items[2].can_rotate);
+}
+
+#[test]
+fn test_import_cut_list_reports_errors_by_row() {
+    let csv = "id,length,width,quantity,rotation\n\
+               a,100,x,2,maybe\n\
+               b,600,300,1,yes\n\
+               c,-5,20,1.5,\n";
+
+    let Err(OptimizerError::InvalidInput(errors)) =
+        crate::import_cut_list(csv.as_bytes(), &CutListOptions::default())
+    else {
+        panic!("expected row errors");
+    };
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        [
+            "rows[2].width",
+            "rows[2].rotation",
+            "rows[4].length",
+            "rows[4].quantity"
+        ]
+    );
+
+    let Err(OptimizerError::InvalidInput(errors)) =
+        crate::import_cut_list(b"name,size\nx,1\n", &CutListOptions::default())
+    else {
+        panic!("expected missing columns");
+    };
+    assert_eq!(errors[0].field, "columns.length");
+    assert_eq!(errors[1].field, "columns.width");
+}
+
+#[test]
+fn test_export_tables_as_csv_and_xlsx() {
+    use calamine::{open_workbook_auto_from_rs, Data, Reader};
+
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "sheet".into(),
+            width: 2440.0,
+            height: 1220.0,
+            ..Default::default()
+        }],
+        items: vec![Item {
+            id: "door".into(),
+            width: 2000.0,
+            height: 1000.0,
+            quantity: 2,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let result = Optimizer::new(request).unwrap().optimize().unwrap();
+    assert_eq!(result.layouts.len(), 2);
+
+    let placements = crate::result_to_csv(&result, ExportTable::Placements).unwrap();
+    let lines: Vec<&str> = placements.lines().collect();
+    assert_eq!(
+        lines[0],
+        "panel_type_id,panel_number,offcut_id,item_id,x,y,width,height,rotated"
+    );
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("sheet,1,,door_1,"));
+
+    let totals = crate::result_to_csv(&result, ExportTable::Totals).unwrap();
+    let lines: Vec<&str> = totals.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("sheet,2,0,5953600,4000000,1953600,67.19,"));
+
+    let xlsx = crate::result_to_xlsx(&result).unwrap();
+    let mut workbook = open_workbook_auto_from_rs(std::io::Cursor::new(xlsx)).unwrap();
+    assert_eq!(workbook.sheet_names(), ["Placements", "Panels", "Totals"]);
+    let panels = workbook.worksheet_range("Panels").unwrap();
+    assert_eq!(panels.height(), 3);
+    assert_eq!(
+        panels.get_value((0, 9)),
+        Some(&Data::String("utilization".into()))
+    );
+    assert_eq!(panels.get_value((1, 9)), Some(&Data::Float(67.19)));
+    let placements = workbook.worksheet_range("Placements").unwrap();
+    assert_eq!(placements.get_value((2, 8)), Some(&Data::Bool(false)));
+}
+
+/// True if an edge-to-edge cut lies between two placements along its whole span.
+fn cut_separates(cut: &Cut, a: &Placement, b: &Placement) -> bool {
+    let eps = 0.5;
+    let (a_lo, a_hi, b_lo, b_hi, a_span, b_span) = match cut.direction {
+        CutDirection::Vertical => (
+            a.x,
+            a.x + a.width,
+            b.x,
+            b.x + b.width,
+            (a.y, a.y + a.height),
+            (b.y, b.y + b.height),
+        ),
+        CutDirection::Horizontal => (
+            a.y,
+            a.y + a.height,
+            b.y,
+            b.y + b.height,
+            (a.x, a.x + a.width),
+            (b.x, b.x + b.width),
+        ),
+    };
+    let spans = |(lo, hi): (f64, f64)| lo >= cut.start - eps && hi <= cut.end + eps;
+    let between = (a_hi <= cut.position + eps && b_lo >= cut.position - eps)
+        || (b_hi <= cut.position + eps && a_lo >= cut.position - eps);
+    between && spans(a_span) && spans(b_span)
+}
+
+#[test]
+fn test_guillotine_cut_tree_covers_every_placement_for_all_strategies() {
+    let content = include_str!("../../../../examples/complex.json");
+    let mut complex: OptimizationRequest = serde_json::from_str(content).unwrap();
+    complex.guillotine = true;
+    let mut shelves = shelves_request();
+    shelves.guillotine = true;
+
+    for request in [complex, shelves] {
+        let optimizer = Optimizer::new(request).unwrap();
+        let expanded_items = optimizer.expand_items();
+        let mut candidates: Vec<Vec<PanelLayout>> = optimizer
+            .generate_sort_strategies(&expanded_items)
+            .iter()
+            .map(|order| optimizer.pack_order(order, &expanded_items).unwrap())
+            .collect();
+        candidates.push(optimizer.optimize().unwrap().layouts);
+
+        for layouts in &candidates {
+            for layout in layouts {
+                let cuts = optimizer
+                    .compute_guillotine_cuts(layout)
+                    .expect("packed layout is not guillotine");
+                assert!(!cuts.is_empty());
+
+                for (i, a) in layout.placements.iter().enumerate() {
+                    for b in &layout.placements[i + 1..] {
+                        assert!(
+                            cuts.iter().any(|cut| cut_separates(cut, a, b)),
+                            "no cut separates {} and {} on panel {}",
+                            a.item_id,
+                            b.item_id,
+                            layout.panel_number
+                        );
+                    }
+                }
+            }
+        }
+
+        for layout in &candidates.last().unwrap()[..] {
+            assert!(!layout.cuts.is_empty());
+        }
+    }
+}
+
+#[test]
+fn test_guillotine_edit_rejects_layout_without_cut_tree() {
+    let item = |id: &str, width: f64, height: f64| Item {
+        id: id.into(),
+        width,
+        height,
+        quantity: 1,
+        ..Default::default()
+    };
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "square".into(),
+            width: 300.0,
+            height: 300.0,
+            ..Default::default()
+        }],
+        items: vec![
+            item("a", 200.0, 100.0),
+            item("b", 100.0, 200.0),
+            item("c", 200.0, 100.0),
+            item("d", 100.0, 100.0),
+        ],
+        guillotine: true,
+        ..Default::default()
+    };
+    let optimizer = Optimizer::new(request).unwrap();
+    let mut result = optimizer.optimize().unwrap();
+
+    let placement = |id: &str, x: f64, y: f64, width: f64, height: f64| Placement {
+        item_id: id.into(),
+        x,
+        y,
+        width,
+        height,
+        ..Default::default()
+    };
+    result.layouts = vec![PanelLayout {
+        panel_type_id: "square".into(),
+        panel_number: 1,
+        width: 300.0,
+        height: 300.0,
+        trimming: 0.0,
+        placements: vec![
+            placement("a", 0.0, 0.0, 200.0, 100.0),
+            placement("b", 200.0, 0.0, 100.0, 200.0),
+            placement("c", 100.0, 200.0, 200.0, 100.0),
+            placement("d", 0.0, 100.0, 100.0, 100.0),
+        ],
+        unused_areas: Vec::new(),
+        cuts: Vec::new(),
+        offcut_id: None,
+    }];
+    let target = PlacementRef {
+        layout: 0,
+        placement: 3,
+    };
+
+    // Still separable: a cut at y = 200, then x = 200 and y = 100 below it
+    let edited = optimizer
+        .apply_edit(
+            &result,
+            &LayoutEdit::Move {
+                target,
+                x: 0.0,
+                y: 200.0,
+            },
+        )
+        .unwrap();
+    assert!(!edited.layouts[0].cuts.is_empty());
+
+    // Straddling y = 200 leaves no edge-to-edge cut on the panel
+    match optimizer.apply_edit(
+        &result,
+        &LayoutEdit::Move {
+            target,
+            x: 0.0,
+            y: 150.0,
+        },
+    ) {
+        Err(OptimizerError::InvalidEdit(violations)) => {
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].kind, ViolationKind::NotGuillotine);
+            assert_eq!(violations[0].location, "layouts[0]");
+        }
+        other => panic!("expected InvalidEdit, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_guillotine_reports_when_every_packing_is_rejected() {
+    let item = |id: &str, width: f64, height: f64| Item {
+        id: id.into(),
+        width,
+        height,
+        quantity: 1,
+        ..Default::default()
+    };
+    let request = OptimizationRequest {
+        panel_types: vec![PanelType {
+            id: "square".into(),
+            width: 300.0,
+            height: 300.0,
+            ..Default::default()
+        }],
+        items: vec![
+            item("a", 200.0, 100.0),
+            item("b", 100.0, 200.0),
+            item("c", 200.0, 100.0),
+            item("d", 100.0, 100.0),
+        ],
+        guillotine: true,
+        ..Default::default()
+    };
+    let optimizer = Optimizer::new(request).unwrap();
+
+    let placement = |id: &str, x: f64, y: f64, width: f64, height: f64| Placement {
+        item_id: id.into(),
+        x,
+        y,
+        width,
+        height,
+        ..Default::default()
+    };
+    // No edge-to-edge cut runs across the whole panel
+    let pinwheel = vec![PanelLayout {
+        panel_type_id: "square".into(),
+        panel_number: 1,
+        width: 300.0,
+        height: 300.0,
+        trimming: 0.0,
+        placements: vec![
+            placement("a", 0.0, 0.0, 200.0, 100.0),
+            placement("b", 200.0, 0.0, 100.0, 200.0),
+            placement("c", 100.0, 200.0, 200.0, 100.0),
+            placement("d", 0.0, 150.0, 100.0, 100.0),
+        ],
+        unused_areas: Vec::new(),
+        cuts: Vec::new(),
+        offcut_id: None,
+    }];
+
+    let order = optimizer.expand_items();
+    let mut candidates = Candidates::default();
+    optimizer.offer_candidate(&mut candidates, &order, Ok(pinwheel));
+    optimizer.offer_candidate(
+        &mut candidates,
+        &order,
+        Err(OptimizerError::InsufficientStock {
+            unplaced_items: vec!["d".into()],
+        }),
+    );
+
+    match optimizer.best_candidate(candidates) {
+        Err(OptimizerError::NotGuillotine) => {}
+        other => panic!("expected NotGuillotine, got {:?}", other.map(|(l, _)| l)),
+    }
+}
@@ -0,0 +1,415 @@
+use super::*;
+use exact::EXACT_MAX_ITEMS;
+use std::collections::{HashMap, HashSet};
+
+/// Checks a request without optimizing it and reports every problem at once,
+/// each with the path of the offending field (such as `items[3].width`).
+/// The request is accepted by `Optimizer::new` exactly when the report has
+/// no errors.
+pub fn validate(request: &OptimizationRequest) -> ValidationReport {
+    let optimizer = Optimizer {
+        request: request.clone(),
+    };
+
+    let mut report = ValidationReport::default();
+    optimizer.check_fields(&mut report);
+
+    // Item sizes are only meaningful against well-formed panel types
+    if report.is_valid() {
+        for (index, item) in optimizer.oversized_items() {
+            report.error(
+                format!("items[{}]", index),
+                format!(
+                    "Item '{}' ({}x{}) fits no panel type in any allowed rotation",
+                    item.id, item.width, item.height
+                ),
+            );
+        }
+    }
+
+    optimizer.check_warnings(&mut report);
+    report
+}
+
+impl ValidationReport {
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(FieldError {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// True for a finite, strictly positive size.
+/// Splits an id shaped like a copy id (`shelf_2`) into item id and copy number.
+fn split_copy_id(id: &str) -> Option<(&str, u32)> {
+    let (base, copy) = id.rsplit_once('_')?;
+    let number: u32 = copy.parse().ok()?;
+    (number > 0 && number.to_string() == copy).then_some((base, number))
+}
+
+fn is_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+impl Optimizer {
+    /// Collects every invalid value of the request.
+    pub(super) fn check_fields(&self, report: &mut ValidationReport) {
+        let request = &self.request;
+
+        if !request.cut_width.is_finite() || request.cut_width < 0.0 {
+            report.error("cut_width", "Cut width must be zero or positive");
+        }
+
+        if let Some(size) = request.min_reusable_remnant_size {
+            if !size.is_finite() || size < 0.0 {
+                report.error(
+                    "min_reusable_remnant_size",
+                    "Minimum reusable remnant size must be zero or positive",
+                );
+            }
+        }
+
+        if request.panel_types.is_empty() {
+            report.error("panel_types", "At least one panel type must be provided");
+        }
+
+        let mut panel_ids = HashSet::new();
+        for (i, panel) in request.panel_types.iter().enumerate() {
+            let path = format!("panel_types[{}]", i);
+
+            if !panel_ids.insert(panel.id.as_str()) {
+                report.error(
+                    format!("{}.id", path),
+                    format!("Duplicate panel type id '{}'", panel.id),
+                );
+            }
+
+            let mut size_valid = true;
+            for (name, value) in [("width", panel.width), ("height", panel.height)] {
+                if !is_positive(value) {
+                    size_valid = false;
+                    report.error(
+                        format!("{}.{}", path, name),
+                        format!("Panel '{}' must have a positive {}", panel.id, name),
+                    );
+                }
+            }
+
+            if !panel.trimming.is_finite() || panel.trimming < 0.0 {
+                report.error(
+                    format!("{}.trimming", path),
+                    format!("Panel '{}' has negative trimming", panel.id),
+                );
+            } else if size_valid
+                && (panel.width - panel.trimming * 2.0 <= 0.0
+                    || panel.height - panel.trimming * 2.0 <= 0.0)
+            {
+                report.error(
+                    format!("{}.trimming", path),
+                    format!(
+                        "Panel '{}' becomes unusable after applying trimming",
+                        panel.id
+                    ),
+                );
+            }
+
+            match panel.cost {
+                Some(cost) if !cost.is_finite() || cost < 0.0 => {
+                    report.error(
+                        format!("{}.cost", path),
+                        format!("Panel '{}' has an invalid cost", panel.id),
+                    );
+                }
+                None if request.objective == Objective::Cost => {
+                    report.error(
+                        format!("{}.cost", path),
+                        format!("Panel '{}' needs a cost when optimizing for cost", panel.id),
+                    );
+                }
+                _ => {}
+            }
+
+            for (j, optional) in panel.optional_items.iter().enumerate() {
+                for (name, value) in [("width", optional.width), ("height", optional.height)] {
+                    if !is_positive(value) {
+                        report.error(
+                            format!("{}.optional_items[{}].{}", path, j, name),
+                            format!(
+                                "Optional item '{}' must have a positive {}",
+                                optional.id, name
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        if request.items.is_empty() {
+            report.error("items", "At least one item must be provided");
+        }
+
+        let mut item_ids = HashSet::new();
+        // Copies of an item with quantity > 1 are placed as `id_1`, `id_2`, ...,
+        // which may clash with the id of an item placed once. Other pairs of
+        // placement ids only clash if the item ids are equal.
+        let mut copied: HashMap<&str, u32> = HashMap::new();
+        let mut single_copies: HashMap<&str, Vec<u32>> = HashMap::new();
+        for (i, item) in request.items.iter().enumerate() {
+            let path = format!("items[{}]", i);
+
+            let clash = match item.quantity {
+                0 => None,
+                1 => split_copy_id(&item.id)
+                    .filter(|(base, copy)| copied.get(base).is_some_and(|q| copy <= q))
+                    .map(|_| item.id.clone()),
+                quantity => single_copies
+                    .get(item.id.as_str())
+                    .and_then(|copies| copies.iter().find(|&&copy| copy <= quantity))
+                    .map(|copy| format!("{}_{}", item.id, copy)),
+            };
+            match item.quantity {
+                0 => {}
+                1 => {
+                    if let Some((base, copy)) = split_copy_id(&item.id) {
+                        single_copies.entry(base).or_default().push(copy);
+                    }
+                }
+                quantity => {
+                    copied.insert(&item.id, quantity);
+                }
+            }
+
+            if !item_ids.insert(item.id.as_str()) {
+                report.error(
+                    format!("{}.id", path),
+                    format!("Duplicate item id '{}'", item.id),
+                );
+            } else if let Some(id) = clash {
+                report.error(
+                    format!("{}.id", path),
+                    format!(
+                        "Item '{}' is placed as '{}', which another item already uses",
+                        item.id, id
+                    ),
+                );
+            }
+
+            let mut size_valid = true;
+            for (name, value) in [("width", item.width), ("height", item.height)] {
+                if !is_positive(value) {
+                    size_valid = false;
+                    report.error(
+                        format!("{}.{}", path, name),
+                        format!("Item '{}' must have a positive {}", item.id, name),
+                    );
+                }
+            }
+
+            if item.quantity == 0 {
+                report.error(
+                    format!("{}.quantity", path),
+                    format!("Item '{}' must have a quantity of at least 1", item.id),
+                );
+            }
+
+            if !request
+                .panel_types
+                .iter()
+                .any(|p| p.material == item.material)
+            {
+                let message = match &item.material {
+                    Some(material) => format!(
+                        "Item '{}' has no panel type of material '{}'",
+                        item.id, material
+                    ),
+                    None => format!(
+                        "Item '{}' has no material, but every panel type has one",
+                        item.id
+                    ),
+                };
+                report.error(format!("{}.material", path), message);
+            }
+
+            let Some(banding) = &item.banding else {
+                continue;
+            };
+
+            let bands = [&banding.top, &banding.bottom, &banding.left, &banding.right];
+            if bands
+                .into_iter()
+                .flatten()
+                .any(|b| !b.thickness.is_finite() || b.thickness < 0.0)
+            {
+                report.error(
+                    format!("{}.banding", path),
+                    format!("Item '{}' has an invalid edge band thickness", item.id),
+                );
+            } else if size_valid
+                && (item.width - banding.width_reduction() <= 0.0
+                    || item.height - banding.height_reduction() <= 0.0)
+            {
+                report.error(
+                    format!("{}.banding", path),
+                    format!("Item '{}' is thinner than its edge banding", item.id),
+                );
+            }
+        }
+
+        if let Some(search) = &request.search {
+            if search.time_limit_ms.is_none() && search.max_iterations.is_none() {
+                report.error("search", "Search needs a time limit or an iteration limit");
+            }
+        }
+
+        let mut offcut_ids = HashSet::new();
+        for (i, offcut) in request.offcuts.iter().enumerate() {
+            let path = format!("offcuts[{}]", i);
+
+            if !offcut_ids.insert(offcut.id.as_str()) {
+                report.error(
+                    format!("{}.id", path),
+                    format!("Duplicate offcut id '{}'", offcut.id),
+                );
+            }
+
+            for (name, value) in [("width", offcut.width), ("height", offcut.height)] {
+                if !is_positive(value) {
+                    report.error(
+                        format!("{}.{}", path, name),
+                        format!("Offcut '{}' must have a positive size", offcut.id),
+                    );
+                }
+            }
+
+            if !request
+                .panel_types
+                .iter()
+                .any(|p| p.id == offcut.panel_type_id)
+            {
+                report.error(
+                    format!("{}.panel_type_id", path),
+                    format!(
+                        "Offcut '{}' refers to unknown panel type '{}'",
+                        offcut.id, offcut.panel_type_id
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Collects values that are accepted but have no effect.
+    fn check_warnings(&self, report: &mut ValidationReport) {
+        let request = &self.request;
+
+        for (i, panel) in request.panel_types.iter().enumerate() {
+            let path = format!("panel_types[{}]", i);
+
+            if !request
+                .items
+                .iter()
+                .any(|item| item.material == panel.material)
+            {
+                report.warning(
+                    format!("{}.material", path),
+                    format!("No item uses the material of panel type '{}'", panel.id),
+                );
+            }
+
+            if panel.available == Some(0) {
+                report.warning(
+                    format!("{}.available", path),
+                    format!("Panel type '{}' has no stock", panel.id),
+                );
+            }
+
+            let usable_width = panel.width - panel.trimming * 2.0;
+            let usable_height = panel.height - panel.trimming * 2.0;
+            for (j, optional) in panel.optional_items.iter().enumerate() {
+                let fits = |w: f64, h: f64| w <= usable_width && h <= usable_height;
+                let fits_any_rotation = fits(optional.width, optional.height)
+                    || (optional.can_rotate && fits(optional.height, optional.width));
+                if !fits_any_rotation {
+                    report.warning(
+                        format!("{}.optional_items[{}]", path, j),
+                        format!(
+                            "Optional item '{}' never fits panel type '{}'",
+                            optional.id, panel.id
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Summed as u64 so huge quantities cannot overflow
+        let total_items: u64 = request.items.iter().map(|i| u64::from(i.quantity)).sum();
+        if request.exact && total_items > EXACT_MAX_ITEMS as u64 {
+            report.warning(
+                "exact",
+                format!(
+                    "The exact search only runs for up to {} items; this request has {}",
+                    EXACT_MAX_ITEMS, total_items
+                ),
+            );
+        }
+    }
+
+    /// Returns the items (with their index) that fit no panel type of their
+    /// material in any allowed rotation.
+    fn oversized_items(&self) -> Vec<(usize, &Item)> {
+        self.request
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.quantity > 0)
+            .filter(|(_, item)| {
+                // Banded items are cut smaller than their finished size
+                let (width_reduction, height_reduction) = item
+                    .banding
+                    .as_ref()
+                    .map(|b| (b.width_reduction(), b.height_reduction()))
+                    .unwrap_or((0.0, 0.0));
+                let cut = Item {
+                    width: item.width - width_reduction,
+                    height: item.height - height_reduction,
+                    ..(*item).clone()
+                };
+                !self
+                    .request
+                    .panel_types
+                    .iter()
+                    .filter(|panel_type| panel_type.material == item.material)
+                    .any(|panel_type| {
+                        self.panel_orientations(panel_type)
+                            .into_iter()
+                            .any(|(w, h)| {
+                                self.best_new_panel_placement(&cut, panel_type, w, h)
+                                    .is_some()
+                            })
+                    })
+            })
+            .collect()
+    }
+
+    /// Fails with `CannotFitAll` listing every item that fits no panel type
+    /// in any allowed rotation.
+    pub(super) fn check_item_sizes(&self) -> Result<()> {
+        let oversized: Vec<&Item> = self
+            .oversized_items()
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect();
+
+        if oversized.is_empty() {
+            return Ok(());
+        }
+        Err(self.cannot_fit(&oversized))
+    }
+}
@@ -0,0 +1,688 @@
+//! PDF export of cutting plans for printing: a cover page with the panel
+//! counts and summary figures, then one page per panel with its drawing,
+//! dimensions and part list. Text uses the standard PDF fonts, so no font
+//! files or external tools are needed.
+
+use crate::types::{OptimizationResult, PanelLayout, PdfOptions};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+/// Space around the page content, in points.
+const MARGIN: f32 = 36.0;
+/// Room below the content for the page number.
+const FOOTER_HEIGHT: f32 = 16.0;
+/// Room above a panel drawing for its title and figures.
+const HEADER_HEIGHT: f32 = 44.0;
+/// Room above and left of a panel drawing for its dimension lines.
+const DIMENSION_SPACE: f32 = 24.0;
+/// Gap between the drawing and the part list, and between list columns.
+const GAP: f32 = 18.0;
+const LIST_WIDTH: f32 = 250.0;
+const ROW_HEIGHT: f32 = 11.0;
+/// Part list columns: number, item, size, position, rotation.
+const LIST_COLUMNS: [f32; 5] = [0.0, 20.0, 115.0, 175.0, 235.0];
+
+type Rgb = (f32, f32, f32);
+
+const TEXT: Rgb = (0.2, 0.2, 0.2);
+const MUTED: Rgb = (0.4, 0.4, 0.4);
+const ITEM_FILL: Rgb = (0.78, 0.9, 0.79);
+const ITEM_STROKE: Rgb = (0.18, 0.49, 0.2);
+const UNUSED_FILL: Rgb = (1.0, 0.89, 0.89);
+const UNUSED_STROKE: Rgb = (0.94, 0.27, 0.27);
+const TRIM_FILL: Rgb = (0.9, 0.91, 0.92);
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+#[derive(Clone, Copy)]
+struct Font {
+    name: Name<'static>,
+    size: f32,
+    color: Rgb,
+}
+
+const TITLE: Font = Font {
+    name: BOLD,
+    size: 20.0,
+    color: TEXT,
+};
+const HEADING: Font = Font {
+    name: BOLD,
+    size: 12.0,
+    color: TEXT,
+};
+const BODY: Font = Font {
+    name: REGULAR,
+    size: 10.0,
+    color: TEXT,
+};
+const NOTE: Font = Font {
+    name: REGULAR,
+    size: 9.0,
+    color: MUTED,
+};
+const SMALL: Font = Font {
+    name: REGULAR,
+    size: 8.0,
+    color: TEXT,
+};
+const SMALL_BOLD: Font = Font {
+    name: BOLD,
+    size: 8.0,
+    color: TEXT,
+};
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Pages written so far, all of one size.
+struct Document {
+    width: f32,
+    height: f32,
+    pages: Vec<Content>,
+}
+
+impl Document {
+    fn new_page(&mut self) -> &mut Content {
+        self.pages.push(Content::new());
+        self.pages.last_mut().expect("a page was just added")
+    }
+
+    fn top(&self) -> f32 {
+        self.height - MARGIN
+    }
+
+    fn bottom(&self) -> f32 {
+        MARGIN + FOOTER_HEIGHT
+    }
+}
+
+/// Text cursor that moves on to a new page when the current one is full.
+struct Flow {
+    y: f32,
+}
+
+impl Flow {
+    /// Reserves a line of `height` and returns the page and baseline to write it on.
+    fn line<'a>(&mut self, document: &'a mut Document, height: f32) -> (&'a mut Content, f32) {
+        if self.y - height < document.bottom() {
+            self.y = document.top();
+            document.new_page();
+        }
+        self.y -= height;
+        let page = document.pages.last_mut().expect("the cover page exists");
+        (page, self.y)
+    }
+}
+
+/// Area of the page in points, `y` being the bottom edge.
+#[derive(Clone, Copy)]
+struct Area {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+/// Renders a result as a printable PDF: a cover page with `panels_required`
+/// and the summary figures, then one page per layout in layout order. A panel
+/// page shows the panel drawn to scale with its overall dimensions and
+/// numbered parts, next to (or, in portrait, below) a list of every part with
+/// its size, position and rotation. Part lists that do not fit continue on
+/// the following pages.
+pub fn render_pdf(result: &OptimizationResult, options: &PdfOptions) -> Vec<u8> {
+    let (long, short) = options.page_size.points();
+    let (width, height) = if options.portrait {
+        (short, long)
+    } else {
+        (long, short)
+    };
+    let title = options.title.as_deref().unwrap_or("Cutting plan");
+
+    let mut document = Document {
+        width,
+        height,
+        pages: Vec::new(),
+    };
+    write_cover(&mut document, result, title);
+    for layout in &result.layouts {
+        write_layout(&mut document, layout, options.portrait);
+    }
+
+    let count = document.pages.len();
+    for (index, page) in document.pages.iter_mut().enumerate() {
+        text(page, NOTE, MARGIN, MARGIN, Align::Left, title);
+        text(
+            page,
+            NOTE,
+            width - MARGIN,
+            MARGIN,
+            Align::Right,
+            &format!("Page {} of {}", index + 1, count),
+        );
+    }
+
+    write_pdf(document, title)
+}
+
+/// Writes the page tree, fonts and document info around the page contents.
+fn write_pdf(document: Document, title: &str) -> Vec<u8> {
+    let catalog = Ref::new(1);
+    let tree = Ref::new(2);
+    let regular = Ref::new(3);
+    let bold = Ref::new(4);
+    let info = Ref::new(5);
+    // Every page takes two ids: the page and its content stream
+    let page_ref = |index: usize| Ref::new(6 + 2 * index as i32);
+    let content_ref = |index: usize| Ref::new(7 + 2 * index as i32);
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog).pages(tree);
+    pdf.pages(tree)
+        .kids((0..document.pages.len()).map(page_ref))
+        .count(document.pages.len() as i32);
+
+    for (index, content) in document.pages.into_iter().enumerate() {
+        let mut page = pdf.page(page_ref(index));
+        page.parent(tree)
+            .media_box(Rect::new(0.0, 0.0, document.width, document.height))
+            .contents(content_ref(index));
+        page.resources()
+            .fonts()
+            .pair(REGULAR, regular)
+            .pair(BOLD, bold);
+        page.finish();
+        pdf.stream(content_ref(index), &content.finish());
+    }
+
+    for (id, name) in [(regular, "Helvetica"), (bold, "Helvetica-Bold")] {
+        pdf.type1_font(id)
+            .base_font(Name(name.as_bytes()))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+    pdf.document_info(info)
+        .title(TextStr(title))
+        .producer(TextStr("openoptimizer"));
+
+    pdf.finish()
+}
+
+fn write_cover(document: &mut Document, result: &OptimizationResult, title: &str) {
+    let summary = &result.summary;
+    let mut flow = Flow {
+        y: document.top() + 8.0,
+    };
+    document.new_page();
+
+    let (page, y) = flow.line(document, 28.0);
+    text(page, TITLE, MARGIN, y, Align::Left, title);
+
+    let mut overview = format!(
+        "{} layouts on {} new panels",
+        result.layouts.len(),
+        summary.total_panels
+    );
+    if !result.offcuts_used.is_empty() {
+        overview.push_str(&format!(" and {} offcuts", result.offcuts_used.len()));
+    }
+    if result.cancelled {
+        overview.push_str(" (best plan found before the run was cancelled)");
+    }
+    let (page, y) = flow.line(document, 16.0);
+    text(page, NOTE, MARGIN, y, Align::Left, &overview);
+
+    let mut panels: Vec<(&String, &u32)> = result.panels_required.iter().collect();
+    panels.sort();
+    let rows = panels
+        .into_iter()
+        .map(|(id, count)| (id.clone(), count.to_string()))
+        .collect();
+    write_table(document, &mut flow, "Panels required", rows);
+    write_table(document, &mut flow, "Summary", summary_rows(result));
+
+    if !result.materials.is_empty() {
+        let rows = result
+            .materials
+            .iter()
+            .map(|material| {
+                (
+                    material
+                        .material
+                        .clone()
+                        .unwrap_or_else(|| "(no material)".into()),
+                    format!(
+                        "{} panels, {:.1}% waste",
+                        material.summary.total_panels, material.summary.waste_percentage
+                    ),
+                )
+            })
+            .collect();
+        write_table(document, &mut flow, "Materials", rows);
+    }
+}
+
+/// Figures of the result summary, as label and value.
+fn summary_rows(result: &OptimizationResult) -> Vec<(String, String)> {
+    let summary = &result.summary;
+    let mut rows = vec![
+        ("Total panels".into(), summary.total_panels.to_string()),
+        ("Total area".into(), square_metres(summary.total_area)),
+        ("Used area".into(), square_metres(summary.used_area)),
+        (
+            "Waste".into(),
+            format!(
+                "{} ({:.1}%)",
+                square_metres(summary.waste_area),
+                summary.waste_percentage
+            ),
+        ),
+    ];
+
+    if let Some(area) = summary.reusable_remnant_area {
+        rows.push(("Reusable remnants".into(), square_metres(area)));
+    }
+    if let (Some(area), Some(percentage)) =
+        (summary.actual_waste_area, summary.actual_waste_percentage)
+    {
+        rows.push((
+            "Actual waste".into(),
+            format!("{} ({:.1}%)", square_metres(area), percentage),
+        ));
+    }
+    if let Some(lower_bound) = summary.lower_bound {
+        let status = match (summary.optimality_proven, summary.optimality_gap) {
+            (Some(true), _) => " (optimal)".to_string(),
+            (_, Some(gap)) => format!(" (gap {})", gap),
+            _ => String::new(),
+        };
+        rows.push(("Lower bound".into(), format!("{}{}", lower_bound, status)));
+    }
+    if let Some(cost) = summary.total_cost {
+        rows.push(("Total cost".into(), format!("{:.2}", cost)));
+        let mut costs: Vec<_> = summary.cost_by_panel_type.iter().collect();
+        costs.sort_by(|a, b| a.0.cmp(b.0));
+        for (panel_type, cost) in costs {
+            rows.push((format!("Cost of {}", panel_type), format!("{:.2}", cost)));
+        }
+    }
+
+    let mut banding: Vec<_> = summary.banding_length.iter().collect();
+    banding.sort_by(|a, b| a.0.cmp(b.0));
+    for (material, length) in banding {
+        rows.push((
+            format!("Edge banding {}", material),
+            format!("{:.0} mm", length),
+        ));
+    }
+
+    if !result.optional_items_used.is_empty() {
+        rows.push((
+            "Optional parts".into(),
+            result.optional_items_used.len().to_string(),
+        ));
+    }
+    rows
+}
+
+/// Writes a heading and a two-column table below the flow cursor.
+fn write_table(
+    document: &mut Document,
+    flow: &mut Flow,
+    heading: &str,
+    rows: Vec<(String, String)>,
+) {
+    let label_width = 200.0;
+    let (page, y) = flow.line(document, 32.0);
+    text(page, HEADING, MARGIN, y, Align::Left, heading);
+
+    for (label, value) in rows {
+        let (page, y) = flow.line(document, 15.0);
+        text(
+            page,
+            BODY,
+            MARGIN,
+            y,
+            Align::Left,
+            &fit(&label, label_width - 8.0, BODY.size),
+        );
+        text(page, BODY, MARGIN + label_width, y, Align::Left, &value);
+    }
+}
+
+/// Writes the pages of one layout: its drawing and as much of the part list
+/// as fits next to it, then the rest of the list on continuation pages.
+fn write_layout(document: &mut Document, layout: &PanelLayout, portrait: bool) {
+    let name = match &layout.offcut_id {
+        Some(offcut_id) => format!("{} (offcut {})", layout.panel_type_id, offcut_id),
+        None => format!("{} #{}", layout.panel_type_id, layout.panel_number),
+    };
+    let used: f64 = layout.placements.iter().map(|p| p.width * p.height).sum();
+    let panel_area = layout.width * layout.height;
+    let mut figures = format!(
+        "{} x {} mm | {} parts | {:.1}% used",
+        mm(layout.width),
+        mm(layout.height),
+        layout.placements.len(),
+        if panel_area > 0.0 {
+            used / panel_area * 100.0
+        } else {
+            0.0
+        }
+    );
+    if layout.trimming > 0.0 {
+        figures.push_str(&format!(" | trimming {} mm", mm(layout.trimming)));
+    }
+
+    let (width, top, bottom) = (document.width, document.top(), document.bottom());
+    let content = Area {
+        x: MARGIN,
+        y: bottom,
+        width: width - 2.0 * MARGIN,
+        height: top - HEADER_HEIGHT - bottom,
+    };
+    let (drawing, list) = if portrait {
+        let list_height = (content.height * 0.4).floor();
+        (
+            Area {
+                y: content.y + list_height + GAP,
+                height: content.height - list_height - GAP,
+                ..content
+            },
+            Area {
+                height: list_height,
+                ..content
+            },
+        )
+    } else {
+        (
+            Area {
+                width: content.width - LIST_WIDTH - GAP,
+                ..content
+            },
+            Area {
+                x: content.x + content.width - LIST_WIDTH,
+                width: LIST_WIDTH,
+                ..content
+            },
+        )
+    };
+
+    let page = document.new_page();
+    write_header(page, &name, &figures, top);
+    draw_panel(page, layout, drawing);
+    let mut written = write_part_list(page, layout, 0, list);
+
+    while written < layout.placements.len() {
+        let page = document.new_page();
+        write_header(page, &format!("{} (continued)", name), &figures, top);
+        written += write_part_list(page, layout, written, content);
+    }
+}
+
+fn write_header(page: &mut Content, name: &str, figures: &str, top: f32) {
+    text(page, HEADING, MARGIN, top - 14.0, Align::Left, name);
+    text(page, NOTE, MARGIN, top - 28.0, Align::Left, figures);
+}
+
+/// Draws a layout to scale inside `area`, with its overall dimensions above
+/// and left of it. Parts carry their id, or their list number when the id
+/// does not fit.
+fn draw_panel(page: &mut Content, layout: &PanelLayout, area: Area) {
+    if layout.width <= 0.0 || layout.height <= 0.0 {
+        return;
+    }
+    let scale = ((area.width - DIMENSION_SPACE) / layout.width as f32)
+        .min((area.height - DIMENSION_SPACE) / layout.height as f32);
+    let left = area.x + DIMENSION_SPACE;
+    let top = area.y + area.height - DIMENSION_SPACE;
+    let panel_width = layout.width as f32 * scale;
+    let panel_height = layout.height as f32 * scale;
+    // Layout coordinates run from the top-left corner downwards, like the SVG drawing
+    let rect = |x: f64, y: f64, w: f64, h: f64| {
+        (
+            left + x as f32 * scale,
+            top - (y + h) as f32 * scale,
+            w as f32 * scale,
+            h as f32 * scale,
+        )
+    };
+
+    page.save_state();
+    page.set_line_width(1.0).set_stroke_rgb(0.2, 0.2, 0.2);
+    if layout.trimming > 0.0 {
+        fill(page, TRIM_FILL);
+        page.rect(left, top - panel_height, panel_width, panel_height)
+            .fill_nonzero_and_stroke();
+        let (x, y, w, h) = rect(
+            layout.trimming,
+            layout.trimming,
+            layout.width - 2.0 * layout.trimming,
+            layout.height - 2.0 * layout.trimming,
+        );
+        page.set_fill_rgb(1.0, 1.0, 1.0)
+            .rect(x, y, w, h)
+            .fill_nonzero();
+    } else {
+        page.set_fill_rgb(1.0, 1.0, 1.0)
+            .rect(left, top - panel_height, panel_width, panel_height)
+            .fill_nonzero_and_stroke();
+    }
+
+    page.set_line_width(0.5);
+    page.set_dash_pattern([3.0, 2.0], 0.0);
+    fill(page, UNUSED_FILL);
+    stroke(page, UNUSED_STROKE);
+    for unused in &layout.unused_areas {
+        let (x, y, w, h) = rect(unused.x, unused.y, unused.width, unused.height);
+        page.rect(x, y, w, h).fill_nonzero_and_stroke();
+    }
+    page.set_dash_pattern([], 0.0);
+
+    fill(page, ITEM_FILL);
+    stroke(page, ITEM_STROKE);
+    for placement in &layout.placements {
+        let (x, y, w, h) = rect(placement.x, placement.y, placement.width, placement.height);
+        page.rect(x, y, w, h).fill_nonzero_and_stroke();
+    }
+    page.restore_state();
+
+    for (index, placement) in layout.placements.iter().enumerate() {
+        let (x, y, w, h) = rect(placement.x, placement.y, placement.width, placement.height);
+        let center = x + w / 2.0;
+        let middle = y + h / 2.0;
+        let size = format!("{} x {}", mm(placement.width), mm(placement.height));
+        let number = (index + 1).to_string();
+
+        if h < SMALL.size + 2.0 {
+            continue;
+        }
+        let label = [placement.item_id.as_str(), number.as_str()]
+            .into_iter()
+            .find(|label| text_width(label, SMALL.size) <= w - 4.0);
+        let Some(label) = label else {
+            continue;
+        };
+        if h >= 2.0 * SMALL.size + 6.0 && text_width(&size, SMALL.size) <= w - 4.0 {
+            text(page, SMALL_BOLD, center, middle + 1.0, Align::Center, label);
+            text(
+                page,
+                SMALL,
+                center,
+                middle - SMALL.size,
+                Align::Center,
+                &size,
+            );
+        } else {
+            text(page, SMALL_BOLD, center, middle - 3.0, Align::Center, label);
+        }
+    }
+
+    // Overall width above the panel, height to its left
+    page.save_state();
+    page.set_line_width(0.5);
+    stroke(page, MUTED);
+    let line_y = top + 8.0;
+    page.move_to(left, line_y)
+        .line_to(left + panel_width, line_y)
+        .move_to(left, line_y - 3.0)
+        .line_to(left, line_y + 3.0)
+        .move_to(left + panel_width, line_y - 3.0)
+        .line_to(left + panel_width, line_y + 3.0);
+    let line_x = left - 8.0;
+    page.move_to(line_x, top)
+        .line_to(line_x, top - panel_height)
+        .move_to(line_x - 3.0, top)
+        .line_to(line_x + 3.0, top)
+        .move_to(line_x - 3.0, top - panel_height)
+        .line_to(line_x + 3.0, top - panel_height)
+        .stroke();
+    page.restore_state();
+
+    text(
+        page,
+        NOTE,
+        left + panel_width / 2.0,
+        line_y + 3.0,
+        Align::Center,
+        &format!("{} mm", mm(layout.width)),
+    );
+    let height_label = format!("{} mm", mm(layout.height));
+    let half = text_width(&height_label, NOTE.size) / 2.0;
+    fill(page, NOTE.color);
+    page.begin_text()
+        .set_font(NOTE.name, NOTE.size)
+        .set_text_matrix([
+            0.0,
+            1.0,
+            -1.0,
+            0.0,
+            line_x - 3.0,
+            top - panel_height / 2.0 - half,
+        ])
+        .show(Str(&encode(&height_label)))
+        .end_text();
+}
+
+/// Writes the part list of a layout from part `start` on, in as many columns
+/// as fit into `area`, and returns how many parts were listed.
+fn write_part_list(page: &mut Content, layout: &PanelLayout, start: usize, area: Area) -> usize {
+    let columns = (((area.width + GAP) / (LIST_WIDTH + GAP)).floor() as usize).max(1);
+    let rows = (((area.height - ROW_HEIGHT) / ROW_HEIGHT).floor() as usize).max(1);
+    let top = area.y + area.height;
+    let parts = &layout.placements[start..];
+    let count = parts.len().min(columns * rows);
+
+    for column in 0..columns.min(count.div_ceil(rows)) {
+        let x = area.x + column as f32 * (LIST_WIDTH + GAP);
+        let headers = ["#", "Item", "Size (mm)", "Position (mm)", "Rot."];
+        for (offset, header) in LIST_COLUMNS.iter().zip(headers) {
+            text(page, SMALL_BOLD, x + offset, top - 8.0, Align::Left, header);
+        }
+        page.save_state();
+        page.set_line_width(0.5);
+        stroke(page, MUTED);
+        page.move_to(x, top - ROW_HEIGHT + 0.5)
+            .line_to(x + LIST_WIDTH, top - ROW_HEIGHT + 0.5)
+            .stroke();
+        page.restore_state();
+
+        let first = column * rows;
+        for (row, placement) in parts[first..count.min(first + rows)].iter().enumerate() {
+            let y = top - ROW_HEIGHT * (row + 2) as f32 + 3.0;
+            let cells = [
+                (start + first + row + 1).to_string(),
+                fit(
+                    &placement.item_id,
+                    LIST_COLUMNS[2] - LIST_COLUMNS[1] - 4.0,
+                    SMALL.size,
+                ),
+                format!("{} x {}", mm(placement.width), mm(placement.height)),
+                format!("{}, {}", mm(placement.x), mm(placement.y)),
+                if placement.rotated { "yes" } else { "" }.to_string(),
+            ];
+            for (offset, cell) in LIST_COLUMNS.iter().zip(&cells) {
+                text(page, SMALL, x + offset, y, Align::Left, cell);
+            }
+        }
+    }
+    count
+}
+
+fn text(page: &mut Content, font: Font, x: f32, y: f32, align: Align, value: &str) {
+    let x = match align {
+        Align::Left => x,
+        Align::Center => x - text_width(value, font.size) / 2.0,
+        Align::Right => x - text_width(value, font.size),
+    };
+    fill(page, font.color);
+    page.begin_text()
+        .set_font(font.name, font.size)
+        .next_line(x, y)
+        .show(Str(&encode(value)))
+        .end_text();
+}
+
+fn fill(page: &mut Content, (r, g, b): Rgb) {
+    page.set_fill_rgb(r, g, b);
+}
+
+fn stroke(page: &mut Content, (r, g, b): Rgb) {
+    page.set_stroke_rgb(r, g, b);
+}
+
+/// Approximate width of text in Helvetica, in points.
+fn text_width(value: &str, size: f32) -> f32 {
+    let em: f32 = value
+        .chars()
+        .map(|c| match c {
+            ' ' | '.' | ',' | ':' | ';' | '!' | '|' | 'i' | 'j' | 'l' | 'I' | '\'' => 0.28,
+            'f' | 't' | 'r' | '(' | ')' | '-' => 0.33,
+            'm' | 'w' | 'M' | 'W' | '%' => 0.85,
+            'A'..='Z' => 0.68,
+            _ => 0.56,
+        })
+        .sum();
+    em * size
+}
+
+/// Shortens text with "..." until it fits into `width` points.
+fn fit(value: &str, width: f32, size: f32) -> String {
+    if text_width(value, size) <= width {
+        return value.to_string();
+    }
+    let mut shortened: String = value.to_string();
+    while !shortened.is_empty() && text_width(&shortened, size) + text_width("...", size) > width {
+        shortened.pop();
+    }
+    shortened + "..."
+}
+
+/// Encodes text for the WinAnsi encoded standard fonts. Latin-1 characters
+/// map to themselves; anything else is replaced by '?'.
+fn encode(value: &str) -> Vec<u8> {
+    value
+        .chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7e | 0xa0..=0xff => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Formats a length in mm with at most one decimal.
+fn mm(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{:.0}", rounded)
+    } else {
+        format!("{:.1}", rounded)
+    }
+}
+
+fn square_metres(area: f64) -> String {
+    format!("{:.2} m\u{b2}", area / 1_000_000.0)
+}
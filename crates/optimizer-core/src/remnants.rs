@@ -0,0 +1,99 @@
+//! Remnant inventory: turns the unused areas of a result into stock entries.
+
+use crate::types::{OptimizationResult, OptimizerError, Remnant, Result, UnusedArea};
+use std::cmp::Ordering;
+
+/// Tolerance in mm when testing areas for overlap or panel edge contact.
+const EPS: f64 = 0.5;
+
+/// Lists the reusable remnants of every layout in a result.
+///
+/// Unused areas may overlap, so each panel keeps a non-overlapping selection
+/// (largest first) of the areas of at least `min_size`. The same selection
+/// decides `Summary::reusable_remnant_area`. Ids have the form
+/// `<panel_type_id>-<panel_number>-R<rank>` (or `<offcut_id>-R<rank>` for
+/// offcut layouts), so the same result always yields the same ids.
+pub fn remnant_inventory(result: &OptimizationResult, min_size: f64) -> Vec<Remnant> {
+    let mut remnants = Vec::new();
+
+    for layout in &result.layouts {
+        let prefix = match &layout.offcut_id {
+            Some(offcut_id) => offcut_id.clone(),
+            None => format!("{}-{}", layout.panel_type_id, layout.panel_number),
+        };
+
+        let selected = select_reusable_areas(layout.unused_areas.clone(), min_size);
+        for (rank, area) in selected.into_iter().enumerate() {
+            let touches_edge = area.x < EPS
+                || area.y < EPS
+                || area.x + area.width > layout.width - EPS
+                || area.y + area.height > layout.height - EPS;
+
+            remnants.push(Remnant {
+                id: format!("{}-R{}", prefix, rank + 1),
+                panel_type_id: layout.panel_type_id.clone(),
+                panel_number: layout.panel_number,
+                offcut_id: layout.offcut_id.clone(),
+                x: area.x,
+                y: area.y,
+                width: area.width,
+                height: area.height,
+                trimmed: layout.trimming > 0.0 || !touches_edge,
+            });
+        }
+    }
+
+    remnants
+}
+
+/// Writes a remnant inventory as CSV with a header row.
+pub fn remnants_to_csv(remnants: &[Remnant]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for remnant in remnants {
+        writer
+            .serialize(remnant)
+            .map_err(|e| OptimizerError::Export(e.to_string()))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| OptimizerError::Export(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| OptimizerError::Export(e.to_string()))
+}
+
+/// Picks a non-overlapping set of unused areas of at least `min_size`,
+/// largest first (ties broken bottom-left first).
+pub(crate) fn select_reusable_areas(mut areas: Vec<UnusedArea>, min_size: f64) -> Vec<UnusedArea> {
+    areas.sort_by(|a, b| {
+        (b.width * b.height)
+            .partial_cmp(&(a.width * a.height))
+            .unwrap_or(Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+            .then(a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal))
+    });
+
+    let mut accepted: Vec<UnusedArea> = Vec::new();
+    for area in areas {
+        if area.width * area.height < min_size {
+            continue;
+        }
+
+        if accepted
+            .iter()
+            .any(|existing| areas_overlap(existing, &area))
+        {
+            continue;
+        }
+
+        accepted.push(area);
+    }
+
+    accepted
+}
+
+fn areas_overlap(a: &UnusedArea, b: &UnusedArea) -> bool {
+    a.x < b.x + b.width - EPS
+        && a.x + a.width > b.x + EPS
+        && a.y < b.y + b.height - EPS
+        && a.y + a.height > b.y + EPS
+}
@@ -0,0 +1,387 @@
+//! SVG rendering of cutting layouts, shared by the CLI and the API.
+
+use crate::types::{
+    FieldError, OptimizationResult, OptimizerError, PanelLayout, Result, Summary, SvgOptions,
+};
+use std::fmt::Write;
+
+/// Space around the drawing, in SVG units.
+const MARGIN: f64 = 20.0;
+/// Room above every panel for its title.
+const TITLE_SPACE: f64 = 20.0;
+/// Gap below every panel but the last.
+const PANEL_GAP: f64 = 20.0;
+const LEGEND_HEIGHT: f64 = 30.0;
+const LEGEND_ENTRY_WIDTH: f64 = 110.0;
+const SUMMARY_WIDTH: f64 = 220.0;
+
+const ITEM_FILL: &str = "#4CAF50";
+const ITEM_STROKE: &str = "#2E7D32";
+const UNUSED_FILL: &str = "#fee2e2";
+const UNUSED_STROKE: &str = "#ef4444";
+const KERF_FILL: &str = "#f59e0b";
+const TRIM_FILL: &str = "#e5e7eb";
+const TRIM_STROKE: &str = "#9ca3af";
+
+/// Renders every layout of a result into one SVG document, stacked top to
+/// bottom, with a legend and the panel count and waste below them.
+pub fn render_svg(result: &OptimizationResult, options: &SvgOptions) -> Result<String> {
+    let scale = drawing_scale(result, options)?;
+    let layouts: Vec<&PanelLayout> = result.layouts.iter().collect();
+    Ok(render(&layouts, Some(&result.summary), options, scale))
+}
+
+/// Renders each layout of a result into its own SVG document, in layout
+/// order. All documents share one scale, so panels can be compared.
+pub fn render_panel_svgs(result: &OptimizationResult, options: &SvgOptions) -> Result<Vec<String>> {
+    let scale = drawing_scale(result, options)?;
+    Ok(result
+        .layouts
+        .iter()
+        .map(|layout| render(&[layout], None, options, scale))
+        .collect())
+}
+
+/// Checks the options and returns the millimetres per SVG unit.
+fn drawing_scale(result: &OptimizationResult, options: &SvgOptions) -> Result<f64> {
+    let invalid = |field: &str, message: String| {
+        OptimizerError::InvalidInput(vec![FieldError {
+            field: field.to_string(),
+            message,
+        }])
+    };
+
+    if !options.kerf.is_finite() || options.kerf < 0.0 {
+        return Err(invalid("kerf", "Kerf must be zero or positive".into()));
+    }
+
+    match options.target_width {
+        Some(width) => {
+            if !width.is_finite() || width <= 0.0 {
+                return Err(invalid(
+                    "target_width",
+                    "Target width must be positive".into(),
+                ));
+            }
+            let widest = result.layouts.iter().map(|l| l.width).fold(0.0, f64::max);
+            Ok(if widest > 0.0 { widest / width } else { 1.0 })
+        }
+        None if options.scale.is_finite() && options.scale > 0.0 => Ok(options.scale),
+        None => Err(invalid("scale", "Scale must be positive".into())),
+    }
+}
+
+fn render(
+    layouts: &[&PanelLayout],
+    summary: Option<&Summary>,
+    options: &SvgOptions,
+    scale: f64,
+) -> String {
+    let mut svg = String::new();
+    write_document(&mut svg, layouts, summary, options, scale)
+        .expect("writing to a String cannot fail");
+    svg
+}
+
+fn write_document(
+    svg: &mut String,
+    layouts: &[&PanelLayout],
+    summary: Option<&Summary>,
+    options: &SvgOptions,
+    scale: f64,
+) -> std::fmt::Result {
+    let legend = if options.legend {
+        legend_entries(options)
+    } else {
+        Vec::new()
+    };
+    let legend_width = if options.legend {
+        legend.len() as f64 * LEGEND_ENTRY_WIDTH + summary.map_or(0.0, |_| SUMMARY_WIDTH)
+    } else {
+        0.0
+    };
+
+    let panels_width = layouts.iter().map(|l| l.width / scale).fold(0.0, f64::max);
+    let panels_height: f64 = layouts
+        .iter()
+        .map(|l| TITLE_SPACE + l.height / scale)
+        .sum::<f64>()
+        + PANEL_GAP * layouts.len().saturating_sub(1) as f64;
+
+    let width = num(panels_width.max(legend_width) + 2.0 * MARGIN);
+    let height =
+        num(panels_height + 2.0 * MARGIN + if options.legend { LEGEND_HEIGHT } else { 0.0 });
+
+    writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    )?;
+    writeln!(
+        svg,
+        r##"  <rect width="100%" height="100%" fill="#f5f5f5"/>"##
+    )?;
+
+    let mut y = MARGIN;
+    for layout in layouts {
+        y += TITLE_SPACE;
+        write_panel(svg, layout, MARGIN, num(y), scale, options)?;
+        y += layout.height / scale + PANEL_GAP;
+    }
+
+    if options.legend {
+        let legend_y = height - MARGIN - 12.0;
+        let mut x = MARGIN;
+        for (label, fill, stroke) in &legend {
+            writeln!(
+                svg,
+                r##"  <rect x="{}" y="{}" width="12" height="12" fill="{}" stroke="{}" stroke-width="1"/>"##,
+                x,
+                legend_y,
+                escape(fill),
+                stroke
+            )?;
+            writeln!(
+                svg,
+                r##"  <text x="{}" y="{}" font-family="Arial" font-size="11" fill="#333">{}</text>"##,
+                x + 16.0,
+                legend_y + 10.0,
+                escape(label)
+            )?;
+            x += LEGEND_ENTRY_WIDTH;
+        }
+
+        if let Some(summary) = summary {
+            writeln!(
+                svg,
+                r##"  <text x="{}" y="{}" font-family="Arial" font-size="12" fill="#666">Panels: {} | Waste: {:.1}%</text>"##,
+                x,
+                legend_y + 10.0,
+                summary.total_panels,
+                summary.waste_percentage
+            )?;
+        }
+    }
+
+    writeln!(svg, "</svg>")
+}
+
+/// Draws one panel with its top-left corner at (`x`, `y`).
+fn write_panel(
+    svg: &mut String,
+    layout: &PanelLayout,
+    x: f64,
+    y: f64,
+    scale: f64,
+    options: &SvgOptions,
+) -> std::fmt::Result {
+    let title = match &layout.offcut_id {
+        Some(offcut_id) => format!("{} (offcut {})", layout.panel_type_id, offcut_id),
+        None => format!("{} #{}", layout.panel_type_id, layout.panel_number),
+    };
+    writeln!(
+        svg,
+        r##"  <text x="{}" y="{}" font-family="Arial" font-size="14" fill="#333">{}</text>"##,
+        x,
+        y - 5.0,
+        escape(&title)
+    )?;
+
+    let trimmed = options.trimming && layout.trimming > 0.0;
+    writeln!(
+        svg,
+        r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#333" stroke-width="2"/>"##,
+        x,
+        y,
+        num(layout.width / scale),
+        num(layout.height / scale),
+        if trimmed { TRIM_FILL } else { "#ffffff" }
+    )?;
+    if trimmed {
+        let trim = layout.trimming / scale;
+        writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#ffffff" stroke="{}" stroke-width="1" stroke-dasharray="4,2"/>"##,
+            num(x + trim),
+            num(y + trim),
+            num(layout.width / scale - 2.0 * trim),
+            num(layout.height / scale - 2.0 * trim),
+            TRIM_STROKE
+        )?;
+    }
+
+    for placement in &layout.placements {
+        let px = num(x + placement.x / scale);
+        let py = num(y + placement.y / scale);
+        let pw = num(placement.width / scale);
+        let ph = num(placement.height / scale);
+
+        writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="{}" stroke-width="1" opacity="0.7"/>"##,
+            px,
+            py,
+            pw,
+            ph,
+            escape(item_color(options, &placement.item_id)),
+            ITEM_STROKE
+        )?;
+
+        let label = if placement.rotated {
+            format!("{} (R)", placement.item_id)
+        } else {
+            placement.item_id.clone()
+        };
+        writeln!(
+            svg,
+            r##"  <text x="{}" y="{}" font-family="Arial" font-size="10" fill="#fff" text-anchor="middle">{}</text>"##,
+            num(px + pw / 2.0),
+            num(py + ph / 2.0 + 3.0),
+            escape(&label)
+        )?;
+
+        if options.dimensions {
+            writeln!(
+                svg,
+                r##"  <text x="{}" y="{}" font-family="Arial" font-size="9" fill="#fff" text-anchor="middle">{} x {}</text>"##,
+                num(px + pw / 2.0),
+                num(py + ph / 2.0 + 15.0),
+                placement.width,
+                placement.height
+            )?;
+        }
+    }
+
+    if options.kerf > 0.0 {
+        // The saw runs along the right and top edge of each part, up to the usable area
+        let usable_right = layout.width - layout.trimming;
+        let usable_top = layout.height - layout.trimming;
+        for placement in &layout.placements {
+            let right = placement.x + placement.width;
+            let top = placement.y + placement.height;
+            let strips = [
+                (
+                    right,
+                    placement.y,
+                    options.kerf.min(usable_right - right),
+                    (placement.height + options.kerf).min(usable_top - placement.y),
+                ),
+                (
+                    placement.x,
+                    top,
+                    placement.width,
+                    options.kerf.min(usable_top - top),
+                ),
+            ];
+            for (sx, sy, sw, sh) in strips {
+                if sw <= 0.0 || sh <= 0.0 {
+                    continue;
+                }
+                writeln!(
+                    svg,
+                    r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" opacity="0.8"/>"##,
+                    num(x + sx / scale),
+                    num(y + sy / scale),
+                    num(sw / scale),
+                    num(sh / scale),
+                    KERF_FILL
+                )?;
+            }
+        }
+    }
+
+    if options.unused_areas {
+        for unused in &layout.unused_areas {
+            let ux = num(x + unused.x / scale);
+            let uy = num(y + unused.y / scale);
+            let uw = num(unused.width / scale);
+            let uh = num(unused.height / scale);
+
+            writeln!(
+                svg,
+                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="{}" stroke-width="1" stroke-dasharray="4,2" opacity="0.6"/>"##,
+                ux, uy, uw, uh, UNUSED_FILL, UNUSED_STROKE
+            )?;
+
+            // Only label areas with room for the text
+            if uw > 40.0 && uh > 20.0 {
+                let area_sqm = (unused.width * unused.height) / 1_000_000.0;
+                writeln!(
+                    svg,
+                    r##"  <text x="{}" y="{}" font-family="Arial" font-size="9" fill="#dc2626" text-anchor="middle" opacity="0.8">{:.2}m²</text>"##,
+                    num(ux + uw / 2.0),
+                    num(uy + uh / 2.0 + 3.0),
+                    area_sqm
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Label, fill and stroke of every legend entry.
+fn legend_entries(options: &SvgOptions) -> Vec<(String, String, &'static str)> {
+    let mut entries = vec![(
+        "Placed items".to_string(),
+        ITEM_FILL.to_string(),
+        ITEM_STROKE,
+    )];
+
+    let mut colored: Vec<(&String, &String)> = options.item_colors.iter().collect();
+    colored.sort();
+    for (id, color) in colored {
+        entries.push((id.clone(), color.clone(), ITEM_STROKE));
+    }
+
+    if options.unused_areas {
+        entries.push((
+            "Unused areas".to_string(),
+            UNUSED_FILL.to_string(),
+            UNUSED_STROKE,
+        ));
+    }
+    if options.kerf > 0.0 {
+        entries.push(("Kerf".to_string(), KERF_FILL.to_string(), KERF_FILL));
+    }
+    if options.trimming {
+        entries.push(("Trimming".to_string(), TRIM_FILL.to_string(), TRIM_STROKE));
+    }
+    entries
+}
+
+/// Fill color of a part: its own id's color, else that of the requested id
+/// it is a copy of (`shelf` for `shelf_2`), else the default.
+fn item_color<'a>(options: &'a SvgOptions, item_id: &str) -> &'a str {
+    options
+        .item_colors
+        .get(item_id)
+        .or_else(|| {
+            let (base, copy) = item_id.rsplit_once('_')?;
+            copy.parse::<u32>().ok()?;
+            options.item_colors.get(base)
+        })
+        .map_or(ITEM_FILL, String::as_str)
+}
+
+/// Rounds a coordinate to 0.01 SVG units, which keeps the markup short.
+fn num(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
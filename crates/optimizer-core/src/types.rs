@@ -1,6 +1,86 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Grain direction of a panel or part, relative to its own width and height
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grain {
+    /// No grain, the piece may be turned freely
+    #[default]
+    None,
+    /// Grain runs parallel to the width
+    AlongWidth,
+    /// Grain runs parallel to the height
+    AlongHeight,
+}
+
+impl Grain {
+    /// Returns true if no grain direction is set.
+    pub fn is_none(&self) -> bool {
+        *self == Grain::None
+    }
+
+    /// Returns the grain direction after turning the piece by 90 degrees.
+    pub fn rotated(self) -> Self {
+        match self {
+            Grain::None => Grain::None,
+            Grain::AlongWidth => Grain::AlongHeight,
+            Grain::AlongHeight => Grain::AlongWidth,
+        }
+    }
+}
+
+/// Edge band glued to one edge of a part
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeBand {
+    /// Band thickness, removed from the part's cut size
+    pub thickness: f64,
+    /// Banding material id, used to total banding length per material
+    pub material: String,
+}
+
+/// Edge banding of a part's four edges.
+/// Bottom and top run along the width (at the low and high y), left and right
+/// run along the height (at the low and high x).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EdgeBanding {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<EdgeBand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<EdgeBand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<EdgeBand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<EdgeBand>,
+}
+
+impl EdgeBanding {
+    /// Total band thickness across the width (left + right).
+    pub fn width_reduction(&self) -> f64 {
+        thickness(&self.left) + thickness(&self.right)
+    }
+
+    /// Total band thickness across the height (bottom + top).
+    pub fn height_reduction(&self) -> f64 {
+        thickness(&self.bottom) + thickness(&self.top)
+    }
+
+    /// Returns the banding after a quarter turn: the left edge becomes the
+    /// bottom edge, the bottom edge becomes the right edge, and so on.
+    pub fn rotated(&self) -> Self {
+        Self {
+            bottom: self.left.clone(),
+            right: self.bottom.clone(),
+            top: self.right.clone(),
+            left: self.top.clone(),
+        }
+    }
+}
+
+fn thickness(band: &Option<EdgeBand>) -> f64 {
+    band.as_ref().map(|b| b.thickness).unwrap_or(0.0)
+}
+
 /// Optional item that can be placed to reduce waste.
 /// These are only considered when effective waste exceeds 8%.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,10 +92,13 @@ pub struct OptionalItem {
     /// Higher priority items are tried first (default: 0)
     #[serde(default)]
     pub priority: i32,
+    /// Required grain direction relative to the item's width/height
+    #[serde(default, skip_serializing_if = "Grain::is_none")]
+    pub grain: Grain,
 }
 
 /// Panel type - describes an available panel size/type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PanelType {
     pub id: String,
     pub width: f64,
@@ -23,6 +106,20 @@ pub struct PanelType {
     /// Uniform border trimmed from every edge before the panel is usable
     #[serde(default)]
     pub trimming: f64,
+    /// Purchase price of a single sheet of this type.
+    /// Required for every panel type when optimizing for `Objective::Cost`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    /// Number of sheets of this type in stock (unlimited when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<u32>,
+    /// Material of the sheet (e.g. "mdf_18"). Items only go on panel types of
+    /// their own material; panel types without material take items without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    /// Grain direction of the sheet. Panels with grain are never turned.
+    #[serde(default, skip_serializing_if = "Grain::is_none")]
+    pub grain: Grain,
     /// Optional filler items that can be placed to reduce waste.
     /// Only considered when effective waste exceeds 8%; sorted by priority (descending).
     #[serde(default)]
@@ -30,17 +127,76 @@ pub struct PanelType {
 }
 
 /// Item to be cut
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Item {
     pub id: String,
     pub width: f64,
     pub height: f64,
     pub quantity: u32,
     pub can_rotate: bool,
+    /// Material the item is cut from; matched against `PanelType::material`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    /// Required grain direction relative to the item's width/height.
+    /// On panels with grain, the item is only placed so both grains line up.
+    #[serde(default, skip_serializing_if = "Grain::is_none")]
+    pub grain: Grain,
+    /// Edge banding per edge. `width` and `height` are the finished size;
+    /// the optimizer cuts the part smaller by the band thicknesses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banding: Option<EdgeBanding>,
+    /// Free text for the workshop, such as a cabinet or drawing reference
+    /// (ignored by the optimizer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Leftover piece of an earlier job that can be cut before opening new sheets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Offcut {
+    pub id: String,
+    pub width: f64,
+    pub height: f64,
+    /// Id of the panel type the offcut was cut from. The offcut shares its grain
+    /// and is reported under that panel type, but never counts as a new sheet.
+    /// Older requests name this field `material`, which is still accepted.
+    #[serde(alias = "material")]
+    pub panel_type_id: String,
+    /// Higher priority offcuts are consumed first (default: 0)
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// What the optimizer minimizes when comparing candidate layouts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Fewest panels first, ties broken by lowest waste
+    #[default]
+    PanelCount,
+    /// Lowest total material cost first, ties broken by panel count and waste
+    Cost,
+}
+
+/// Budget and seed of the optional search mode (see `OptimizationRequest::search`).
+/// At least one of the two budgets must be set; the search stops at whichever
+/// runs out first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// Wall-clock budget in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_ms: Option<u64>,
+    /// Maximum number of candidate orderings to evaluate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<u64>,
+    /// Seed of the random generator. With only an iteration budget, the same
+    /// seed always produces the same result.
+    #[serde(default)]
+    pub seed: u64,
 }
 
 /// Input: What user provides
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OptimizationRequest {
     pub cut_width: f64,
     pub panel_types: Vec<PanelType>,
@@ -55,10 +211,26 @@ pub struct OptimizationRequest {
     /// Try to optimize for leaving large reusable remnants
     #[serde(default)]
     pub optimize_for_reusable_remnants: bool,
+    /// What the optimizer minimizes (default: panel count)
+    #[serde(default)]
+    pub objective: Objective,
+    /// Only produce layouts that can be cut with edge-to-edge (guillotine) cuts
+    #[serde(default)]
+    pub guillotine: bool,
+    /// Improve the heuristic result with a budgeted local search (off when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<SearchOptions>,
+    /// Run an exact search on small instances to prove the panel count optimal
+    /// (reported as `Summary::lower_bound` and `Summary::optimality_proven`)
+    #[serde(default)]
+    pub exact: bool,
+    /// Existing offcuts, consumed before any new panel is opened
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub offcuts: Vec<Offcut>,
 }
 
 /// Placement of an item on a panel
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Placement {
     pub item_id: String,
     pub x: f64,
@@ -66,6 +238,13 @@ pub struct Placement {
     pub width: f64,
     pub height: f64,
     pub rotated: bool,
+    /// Grain direction of the cut part relative to the item's own width/height
+    /// (only set when the panel has grain)
+    #[serde(default, skip_serializing_if = "Grain::is_none")]
+    pub grain: Grain,
+    /// Edge banding of the part in layout orientation (edges follow `rotated`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banding: Option<EdgeBanding>,
 }
 
 /// Rectangular unused (leftover) area on a panel after placements.
@@ -78,8 +257,33 @@ pub struct UnusedArea {
     pub height: f64,
 }
 
-/// Layout of a single panel
+/// Orientation of a straight cut across a panel region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CutDirection {
+    /// Cut along the height at a fixed x position
+    Vertical,
+    /// Cut along the width at a fixed y position
+    Horizontal,
+}
+
+/// Single edge-to-edge cut of a guillotine cutting plan
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cut {
+    /// Cutting stage, starting at 1 for the cuts across the whole (trimmed) panel.
+    /// Each stage cuts the pieces produced by the previous stage.
+    pub stage: u32,
+    pub direction: CutDirection,
+    /// x of a vertical cut or y of a horizontal cut (start of the kerf)
+    pub position: f64,
+    /// Where the cut line begins along the other axis
+    pub start: f64,
+    /// Where the cut line ends along the other axis
+    pub end: f64,
+}
+
+/// Layout of a single panel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PanelLayout {
     pub panel_type_id: String,
     pub panel_number: u32,
@@ -93,11 +297,20 @@ pub struct PanelLayout {
     /// Rectangles with the largest area (sq meters) are preferred when free space must be split.
     #[serde(default)]
     pub unused_areas: Vec<UnusedArea>,
+    /// Guillotine cut sequence (only filled in guillotine mode).
+    /// Cuts are listed depth-first: the cuts of a region come before the cuts
+    /// of the pieces they produce, so the list encodes the cut tree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cuts: Vec<Cut>,
+    /// Id of the offcut this layout was cut from (`None` for new panels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offcut_id: Option<String>,
 }
 
 /// Summary statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Summary {
+    /// Number of new panels (offcuts are not counted)
     pub total_panels: u32,
     pub total_area: f64,
     pub used_area: f64,
@@ -112,30 +325,504 @@ pub struct Summary {
     /// Actual waste percentage (excluding reusable remnants)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub actual_waste_percentage: Option<f64>,
+    /// Total material cost (only reported when panel types carry a cost)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_cost: Option<f64>,
+    /// Material cost per panel type id (only panel types with a cost are listed)
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub cost_by_panel_type: HashMap<String, f64>,
+    /// Total edge banding length per banding material
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub banding_length: HashMap<String, f64>,
+    /// Lower bound on the number of new panels (only in exact mode)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub lower_bound: Option<u32>,
+    /// Whether `total_panels` was proven to be the minimum (only in exact mode)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub optimality_proven: Option<bool>,
+    /// Panels above the lower bound, `total_panels - lower_bound` (only in exact mode)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub optimality_gap: Option<u32>,
+}
+
+/// Panels and statistics of a single material group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialResult {
+    /// Material key (`None` for items and panel types without material)
+    pub material: Option<String>,
+    pub panels_required: HashMap<String, u32>,
+    pub summary: Summary,
 }
 
 /// Output: What optimizer returns
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizationResult {
-    /// How many panels of each type are needed
+    /// How many panels of each type are needed (all materials combined)
     pub panels_required: HashMap<String, u32>,
     /// Detailed cutting layouts for each panel
     pub layouts: Vec<PanelLayout>,
-    /// Overall statistics
+    /// Overall statistics (all materials combined)
     pub summary: Summary,
+    /// Panels and statistics per material, in order of first appearance among
+    /// the items (only filled when some item or panel type has a material)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub materials: Vec<MaterialResult>,
     /// Optional items that were used (if any)
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub optional_items_used: Vec<String>,
+    /// Ids of the offcuts that were cut (if any)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub offcuts_used: Vec<String>,
+    /// True when the run was cancelled and the result is the best solution
+    /// found until then
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub cancelled: bool,
+}
+
+/// Stage of an optimization run, as reported to progress callbacks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Phase {
+    /// Packing with sort strategy `index` (1-based) of `total`
+    Strategy { index: usize, total: usize },
+    /// Removing panels from the packing of strategy `index` of `total`
+    PanelReduction { index: usize, total: usize },
+    /// Local search, after `iteration` candidate orders
+    Search { iteration: u64 },
+    /// Exact search, trying to fit everything on `max_panels` new panels
+    Exact { max_panels: u32 },
+    /// Adding optional items to the final layouts
+    OptionalItems,
+}
+
+/// Progress report of a running optimization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Progress {
+    pub phase: Phase,
+    /// Material group being packed (`None` for items without material)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub material: Option<String>,
+    /// New panels of the best complete solution of this group so far
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub best_panels: Option<u32>,
+    /// Waste percentage of that solution
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub best_waste_percentage: Option<f64>,
+    /// Layouts of that solution, only sent on the report right after it was found
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub layouts: Vec<PanelLayout>,
+}
+
+/// Reusable remnant left on a panel, as listed in the remnant inventory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remnant {
+    /// Stable id derived from the source panel and the remnant's rank on it
+    pub id: String,
+    pub panel_type_id: String,
+    pub panel_number: u32,
+    /// Offcut the remnant was left on (when the source was an offcut).
+    /// Always serialized so CSV exports keep a fixed set of columns.
+    #[serde(default)]
+    pub offcut_id: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// True when every edge of the remnant is a clean cut. False when the
+    /// source panel was not trimmed and the remnant shares a factory edge.
+    pub trimmed: bool,
+}
+
+/// Drawing options of `render_svg` and `render_panel_svgs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SvgOptions {
+    /// Millimetres per SVG unit (ignored when `target_width` is set)
+    pub scale: f64,
+    /// Width in SVG units to draw the widest panel at; sets the scale
+    pub target_width: Option<f64>,
+    /// Fill color per item id. A requested id (`shelf`) also colors its
+    /// copies (`shelf_1`, `shelf_2`, ...); other parts use the default color.
+    pub item_colors: HashMap<String, String>,
+    /// Label every part with its width x height in mm
+    pub dimensions: bool,
+    /// Draws the saw cut (in mm) to the right of and above every part
+    pub kerf: f64,
+    /// Shades the trimmed border of every panel
+    pub trimming: bool,
+    /// Draws the unused areas of every panel
+    pub unused_areas: bool,
+    /// Adds a legend and, for a whole result, the panel count and waste
+    pub legend: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            scale: 2.0,
+            target_width: None,
+            item_colors: HashMap::new(),
+            dimensions: false,
+            kerf: 0.0,
+            trimming: false,
+            unused_areas: true,
+            legend: true,
+        }
+    }
+}
+
+/// Paper format of `render_pdf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    /// 297 x 210 mm
+    #[default]
+    A4,
+    /// 420 x 297 mm
+    A3,
+    /// 11 x 8.5 in
+    Letter,
+}
+
+impl PageSize {
+    /// Long and short edge in PDF points (1/72 in)
+    pub fn points(self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (841.89, 595.28),
+            PageSize::A3 => (1190.55, 841.89),
+            PageSize::Letter => (792.0, 612.0),
+        }
+    }
+}
+
+/// Page options of `render_pdf`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    /// Prints pages upright instead of sideways
+    pub portrait: bool,
+    /// Heading of the cover page (defaults to "Cutting plan")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Drawing unit of `render_dxf` and `render_panel_dxfs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DxfUnits {
+    #[default]
+    #[serde(rename = "mm")]
+    Millimeters,
+    #[serde(rename = "cm")]
+    Centimeters,
+    #[serde(rename = "m")]
+    Meters,
+    #[serde(rename = "in")]
+    Inches,
+}
+
+impl DxfUnits {
+    /// Drawing units per millimetre
+    pub fn per_mm(self) -> f64 {
+        match self {
+            DxfUnits::Millimeters => 1.0,
+            DxfUnits::Centimeters => 0.1,
+            DxfUnits::Meters => 0.001,
+            DxfUnits::Inches => 1.0 / 25.4,
+        }
+    }
+
+    /// `$INSUNITS` code of the unit, written as a hint only: R12 readers
+    /// ignore it, so coordinates are converted with `per_mm` instead
+    pub fn code(self) -> u8 {
+        match self {
+            DxfUnits::Inches => 1,
+            DxfUnits::Millimeters => 4,
+            DxfUnits::Centimeters => 5,
+            DxfUnits::Meters => 6,
+        }
+    }
+}
+
+/// Options of `render_dxf` and `render_panel_dxfs`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DxfOptions {
+    /// Unit of the drawing; layout values are converted from mm, so the
+    /// default `mm` keeps every coordinate exactly as in the layout. The
+    /// `$INSUNITS` header naming it is only a hint
+    pub units: DxfUnits,
+}
+
+/// Column headers and file settings of `import_cut_list`. Headers are
+/// matched case-insensitively; only the length and width columns are required.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CutListOptions {
+    /// Item id; rows without one are numbered `row<N>`
+    pub id_column: String,
+    /// Length of the part, read into `Item::width`
+    pub length_column: String,
+    /// Width of the part, read into `Item::height`
+    pub width_column: String,
+    /// Number of copies (default 1)
+    pub quantity_column: String,
+    /// Whether the part may be rotated: yes/no, true/false, y/n, 1/0 or x for
+    /// yes (default yes)
+    pub rotation_column: String,
+    pub material_column: String,
+    pub notes_column: String,
+    /// CSV field delimiter; detected from the header row (`,`, `;` or tab) when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<char>,
+    /// Worksheet of a spreadsheet file to read (default: the first)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet: Option<String>,
+}
+
+impl Default for CutListOptions {
+    fn default() -> Self {
+        Self {
+            id_column: "id".into(),
+            length_column: "length".into(),
+            width_column: "width".into(),
+            quantity_column: "quantity".into(),
+            rotation_column: "rotation".into(),
+            material_column: "material".into(),
+            notes_column: "notes".into(),
+            delimiter: None,
+            sheet: None,
+        }
+    }
+}
+
+/// Table of a result written by `result_to_csv`; `result_to_xlsx` writes
+/// all of them as separate worksheets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportTable {
+    /// One row per placement with panel, item id, position, size and rotation
+    #[default]
+    Placements,
+    /// One row per layout with its size, part count and utilization
+    Panels,
+    /// One row per panel type with `panels_required`, areas and cost
+    Totals,
+}
+
+/// Kind of problem found by `verify` in a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// Layout refers to a panel type or offcut the request does not have
+    UnknownPanel,
+    /// Layout size or trimming differs from its panel type or offcut
+    PanelMismatch,
+    /// More sheets of a panel type than in stock, or an offcut used twice
+    StockExceeded,
+    /// Placement of an id that is neither an item copy nor an optional item
+    UnknownItem,
+    /// Item copy placed more than once
+    ExtraItem,
+    /// Item copy not placed at all
+    MissingItem,
+    /// Placement size differs from the item's cut size
+    WrongSize,
+    /// Item placed rotated although `can_rotate` is false
+    RotationNotAllowed,
+    /// Item grain does not line up with the panel grain
+    GrainMismatch,
+    /// Item placed on a panel of another material
+    MaterialMismatch,
+    /// Optional item placed on a panel type it does not belong to, or offcut
+    /// layout labelled with a panel type other than the offcut's
+    WrongPanelType,
+    /// Placement reaches outside the panel
+    OutOfBounds,
+    /// Placement reaches into the trimmed border
+    TrimmingViolation,
+    /// Two placements overlap
+    Overlap,
+    /// Two placements are closer than the cut width
+    KerfViolation,
+    /// Panel cannot be cut with edge-to-edge cuts (only reported by
+    /// `Optimizer::apply_edit` in guillotine mode)
+    NotGuillotine,
+}
+
+/// One problem found by `verify`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    /// Path of the offending part of the result, such as
+    /// `layouts[1].placements[4]`, or `items[2]` for missing copies
+    pub location: String,
+    pub message: String,
+}
+
+/// One placement of a result, by index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacementRef {
+    /// Index in `OptimizationResult::layouts`
+    pub layout: usize,
+    /// Index in the layout's `placements`
+    pub placement: usize,
+}
+
+/// Manual change to a result, applied by `Optimizer::apply_edit`.
+/// Positions are the bottom-left corner of the part on its panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum LayoutEdit {
+    /// Moves a part to a new position on its panel
+    Move {
+        target: PlacementRef,
+        x: f64,
+        y: f64,
+    },
+    /// Turns a part by 90 degrees, keeping its bottom-left corner
+    Rotate { target: PlacementRef },
+    /// Exchanges the positions of two parts, on the same or different panels
+    Swap {
+        first: PlacementRef,
+        second: PlacementRef,
+    },
+    /// Moves a part to a position on another panel
+    MoveToPanel {
+        target: PlacementRef,
+        layout: usize,
+        x: f64,
+        y: f64,
+    },
+    /// Takes a part off its panel; panels left empty are dropped
+    Remove { target: PlacementRef },
+    /// Places an item copy that is not placed yet, or an optional item
+    Add {
+        layout: usize,
+        item_id: String,
+        x: f64,
+        y: f64,
+        #[serde(default)]
+        rotated: bool,
+    },
 }
 
 /// Error type for optimization
 #[derive(Debug, thiserror::Error)]
 pub enum OptimizerError {
-    #[error("Cannot fit all items - need more/larger panels")]
-    CannotFitAll,
+    #[error(
+        "Cannot fit all items - need more/larger panels: {}{}",
+        items.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+        largest_panel.as_ref().map(|p| format!(" (largest usable panel: {})", p)).unwrap_or_default()
+    )]
+    CannotFitAll {
+        /// Items that fit no panel type in any allowed rotation
+        items: Vec<ItemSize>,
+        /// Panel type with the largest usable area (after trimming)
+        largest_panel: Option<PanelSize>,
+    },
+
+    #[error("Not enough panel stock to place items: {}", unplaced_items.join(", "))]
+    InsufficientStock { unplaced_items: Vec<String> },
+
+    /// Guillotine mode only: every packing found had a panel that cannot be
+    /// cut with edge-to-edge cuts
+    #[error("No layout found that can be cut with edge-to-edge cuts")]
+    NotGuillotine,
+
+    #[error(
+        "Invalid input: {}",
+        .0.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidInput(Vec<FieldError>),
+
+    #[error(
+        "Invalid edit: {}",
+        .0.iter().map(|v| v.message.as_str()).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidEdit(Vec<Violation>),
+
+    #[error("Export failed: {0}")]
+    Export(String),
+}
+
+impl OptimizerError {
+    /// Machine-readable kind of the error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            OptimizerError::CannotFitAll { .. } => ErrorCode::CannotFit,
+            OptimizerError::InsufficientStock { .. } => ErrorCode::InsufficientStock,
+            OptimizerError::NotGuillotine => ErrorCode::NotGuillotine,
+            OptimizerError::InvalidInput(_) => ErrorCode::InvalidInput,
+            OptimizerError::InvalidEdit(_) => ErrorCode::InvalidEdit,
+            OptimizerError::Export(_) => ErrorCode::ExportFailed,
+        }
+    }
+}
+
+/// Machine-readable error codes, serialized in snake_case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidInput,
+    CannotFit,
+    InsufficientStock,
+    /// No guillotine-cuttable layout was found
+    NotGuillotine,
+    /// Edited layout would overlap, leave too little kerf or leave the panel
+    InvalidEdit,
+    ExportFailed,
+}
+
+/// Problem with one field of a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Path of the offending field, such as `items[3].width`
+    pub field: String,
+    pub message: String,
+}
+
+/// Every problem found in a request by `validate`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Problems that make `Optimizer::new` reject the request
+    pub errors: Vec<FieldError>,
+    /// Values that are accepted but probably not what was meant
+    pub warnings: Vec<FieldError>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Item reported by `OptimizerError::CannotFitAll`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemSize {
+    pub id: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl std::fmt::Display for ItemSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}x{})", self.id, self.width, self.height)
+    }
+}
+
+/// Usable size of a panel type (after trimming)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelSize {
+    pub panel_type_id: String,
+    pub width: f64,
+    pub height: f64,
+}
 
-    #[error("Invalid input: {0}")]
-    InvalidInput(String),
+impl std::fmt::Display for PanelSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}x{}", self.panel_type_id, self.width, self.height)
+    }
 }
 
 pub type Result<T> = std::result::Result<T, OptimizerError>;
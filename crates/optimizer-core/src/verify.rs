@@ -0,0 +1,395 @@
+//! Result verification: checks that a result is a physically valid cutting
+//! plan for its request, independently of how the result was produced.
+
+use crate::types::{
+    Item, OptimizationRequest, OptimizationResult, PanelType, Placement, Violation, ViolationKind,
+};
+use std::collections::HashMap;
+
+/// Tolerance in mm for size and position comparisons.
+const EPS: f64 = 1e-6;
+
+/// Item a placement id may stand for, at its cut size
+struct Source {
+    item: Item,
+    /// Index in `request.items`; `None` for optional items
+    index: Option<usize>,
+    /// Panel type an optional item belongs to
+    panel_type_id: Option<String>,
+}
+
+/// Checks a result against its request and lists every violation: layouts
+/// that do not match a panel type, offcut or the stock, placements that
+/// overlap, leave less than the cut width between them, reach outside the
+/// panel or into its trimmed border, have the wrong size, rotation, grain or
+/// material, optional items on a panel type they do not belong to, and item
+/// copies that are missing or placed more than once.
+///
+/// Placement ids follow the optimizer's convention: an item with quantity
+/// `n > 1` is placed as `<id>_1` to `<id>_n`; optional items keep their id and
+/// may be placed any number of times. An empty list means the result is valid.
+pub fn verify(request: &OptimizationRequest, result: &OptimizationResult) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut report = |kind, location: String, message: String| {
+        violations.push(Violation {
+            kind,
+            location,
+            message,
+        })
+    };
+
+    let sources = expected_copies(request);
+    let mut placed: HashMap<&str, u32> = HashMap::new();
+    let mut sheets: HashMap<&str, u32> = HashMap::new();
+    let mut offcuts_used: HashMap<&str, usize> = HashMap::new();
+
+    for (i, layout) in result.layouts.iter().enumerate() {
+        let location = format!("layouts[{}]", i);
+        let panel_type = request
+            .panel_types
+            .iter()
+            .find(|p| p.id == layout.panel_type_id);
+
+        if panel_type.is_none() {
+            report(
+                ViolationKind::UnknownPanel,
+                location.clone(),
+                format!("Unknown panel type '{}'", layout.panel_type_id),
+            );
+        }
+
+        // Size and trimming the layout should have
+        let expected = match &layout.offcut_id {
+            Some(offcut_id) => match request.offcuts.iter().find(|o| &o.id == offcut_id) {
+                None => {
+                    report(
+                        ViolationKind::UnknownPanel,
+                        location.clone(),
+                        format!("Unknown offcut '{}'", offcut_id),
+                    );
+                    None
+                }
+                Some(offcut) => {
+                    if layout.panel_type_id != offcut.panel_type_id {
+                        report(
+                            ViolationKind::WrongPanelType,
+                            location.clone(),
+                            format!(
+                                "Offcut '{}' is of panel type '{}', not '{}'",
+                                offcut_id, offcut.panel_type_id, layout.panel_type_id
+                            ),
+                        );
+                    }
+                    if let Some(first) = offcuts_used.insert(offcut_id, i) {
+                        report(
+                            ViolationKind::StockExceeded,
+                            location.clone(),
+                            format!(
+                                "Offcut '{}' is already used by layouts[{}]",
+                                offcut_id, first
+                            ),
+                        );
+                    }
+                    Some((offcut.width, offcut.height, 0.0))
+                }
+            },
+            None => panel_type.map(|p| {
+                let count = sheets.entry(&p.id).or_default();
+                *count += 1;
+                if p.available.is_some_and(|available| *count > available) {
+                    report(
+                        ViolationKind::StockExceeded,
+                        location.clone(),
+                        format!(
+                            "Panel type '{}' has only {} sheets in stock",
+                            p.id,
+                            p.available.unwrap_or_default()
+                        ),
+                    );
+                }
+                (p.width, p.height, p.trimming)
+            }),
+        };
+
+        if let Some((width, height, trimming)) = expected {
+            let turnable = panel_type.is_some_and(|p| p.grain.is_none());
+            let same =
+                |w: f64, h: f64| (layout.width - w).abs() < EPS && (layout.height - h).abs() < EPS;
+            let matches_panel = same(width, height) || (turnable && same(height, width));
+            if !matches_panel {
+                report(
+                    ViolationKind::PanelMismatch,
+                    location.clone(),
+                    format!(
+                        "Layout is {}x{}, but its panel is {}x{}",
+                        layout.width, layout.height, width, height
+                    ),
+                );
+            }
+            if (layout.trimming - trimming).abs() > EPS {
+                report(
+                    ViolationKind::PanelMismatch,
+                    location.clone(),
+                    format!(
+                        "Layout trimming is {}, but its panel needs {}",
+                        layout.trimming, trimming
+                    ),
+                );
+            }
+        }
+
+        let trimming = expected.map_or(layout.trimming, |(_, _, t)| t.max(layout.trimming));
+        for (j, placement) in layout.placements.iter().enumerate() {
+            let location = format!("{}.placements[{}]", location, j);
+
+            match sources.get(placement.item_id.as_str()) {
+                None => report(
+                    ViolationKind::UnknownItem,
+                    location.clone(),
+                    format!("Unknown item '{}'", placement.item_id),
+                ),
+                Some(source) => {
+                    let count = placed.entry(&placement.item_id).or_default();
+                    *count += 1;
+                    if *count > 1 && source.index.is_some() {
+                        report(
+                            ViolationKind::ExtraItem,
+                            location.clone(),
+                            format!("Item '{}' is placed more than once", placement.item_id),
+                        );
+                    }
+                    check_item(source, panel_type, placement, &location, &mut report);
+                }
+            }
+
+            let right = placement.x + placement.width;
+            let top = placement.y + placement.height;
+            if placement.x < -EPS
+                || placement.y < -EPS
+                || right > layout.width + EPS
+                || top > layout.height + EPS
+            {
+                report(
+                    ViolationKind::OutOfBounds,
+                    location.clone(),
+                    format!(
+                        "Item '{}' reaches outside the {}x{} panel",
+                        placement.item_id, layout.width, layout.height
+                    ),
+                );
+            } else if placement.x < trimming - EPS
+                || placement.y < trimming - EPS
+                || right > layout.width - trimming + EPS
+                || top > layout.height - trimming + EPS
+            {
+                report(
+                    ViolationKind::TrimmingViolation,
+                    location.clone(),
+                    format!(
+                        "Item '{}' reaches into the {} trimmed border",
+                        placement.item_id, trimming
+                    ),
+                );
+            }
+        }
+
+        for (a, first) in layout.placements.iter().enumerate() {
+            for (b, second) in layout.placements.iter().enumerate().skip(a + 1) {
+                let location = format!("{}.placements[{}]", location, b);
+                let gap_x =
+                    (second.x - (first.x + first.width)).max(first.x - (second.x + second.width));
+                let gap_y =
+                    (second.y - (first.y + first.height)).max(first.y - (second.y + second.height));
+
+                if gap_x < -EPS && gap_y < -EPS {
+                    report(
+                        ViolationKind::Overlap,
+                        location,
+                        format!(
+                            "Item '{}' overlaps item '{}' (placements[{}])",
+                            second.item_id, first.item_id, a
+                        ),
+                    );
+                } else if (gap_y < -EPS && gap_x < request.cut_width - EPS)
+                    || (gap_x < -EPS && gap_y < request.cut_width - EPS)
+                {
+                    report(
+                        ViolationKind::KerfViolation,
+                        location,
+                        format!(
+                            "Item '{}' is {} from item '{}' (placements[{}]), less than the cut width {}",
+                            second.item_id,
+                            gap_x.max(gap_y),
+                            first.item_id,
+                            a,
+                            request.cut_width
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    for (index, item) in request.items.iter().enumerate() {
+        let missing: Vec<String> = copy_ids(item)
+            .filter(|id| !placed.contains_key(id.as_str()))
+            .collect();
+        if !missing.is_empty() {
+            report(
+                ViolationKind::MissingItem,
+                format!("items[{}]", index),
+                format!(
+                    "Item '{}' has {} of {} copies placed, missing {}",
+                    item.id,
+                    item.quantity as usize - missing.len(),
+                    item.quantity,
+                    missing.join(", ")
+                ),
+            );
+        }
+    }
+
+    violations
+}
+
+/// Checks the size, rotation, grain and material of one placed item.
+fn check_item(
+    source: &Source,
+    panel_type: Option<&PanelType>,
+    placement: &Placement,
+    location: &str,
+    report: &mut impl FnMut(ViolationKind, String, String),
+) {
+    let item = &source.item;
+    let (width, height) = if placement.rotated {
+        (item.height, item.width)
+    } else {
+        (item.width, item.height)
+    };
+    if (placement.width - width).abs() > EPS || (placement.height - height).abs() > EPS {
+        report(
+            ViolationKind::WrongSize,
+            location.to_string(),
+            format!(
+                "Item '{}' is placed as {}x{}, but is cut at {}x{}",
+                placement.item_id, placement.width, placement.height, width, height
+            ),
+        );
+    }
+
+    if placement.rotated && !item.can_rotate {
+        report(
+            ViolationKind::RotationNotAllowed,
+            location.to_string(),
+            format!("Item '{}' may not be rotated", placement.item_id),
+        );
+    }
+
+    let Some(panel_type) = panel_type else {
+        return;
+    };
+
+    let placed_grain = if placement.rotated {
+        item.grain.rotated()
+    } else {
+        item.grain
+    };
+    if !item.grain.is_none() && !panel_type.grain.is_none() && placed_grain != panel_type.grain {
+        report(
+            ViolationKind::GrainMismatch,
+            location.to_string(),
+            format!(
+                "Grain of item '{}' does not line up with panel type '{}'",
+                placement.item_id, panel_type.id
+            ),
+        );
+    }
+
+    if let Some(owner) = &source.panel_type_id {
+        if *owner != panel_type.id {
+            report(
+                ViolationKind::WrongPanelType,
+                location.to_string(),
+                format!(
+                    "Optional item '{}' belongs to panel type '{}', not '{}'",
+                    placement.item_id, owner, panel_type.id
+                ),
+            );
+        }
+    }
+
+    if source.index.is_some() && item.material != panel_type.material {
+        report(
+            ViolationKind::MaterialMismatch,
+            location.to_string(),
+            format!(
+                "Item '{}' is placed on panel type '{}' of another material",
+                placement.item_id, panel_type.id
+            ),
+        );
+    }
+}
+
+/// Placement ids of every copy of an item.
+fn copy_ids(item: &Item) -> impl Iterator<Item = String> + '_ {
+    (1..=item.quantity).map(move |copy| {
+        if item.quantity > 1 {
+            format!("{}_{}", item.id, copy)
+        } else {
+            item.id.clone()
+        }
+    })
+}
+
+/// Maps every valid placement id to the item it stands for.
+fn expected_copies(request: &OptimizationRequest) -> HashMap<String, Source> {
+    let mut sources = HashMap::new();
+
+    for panel_type in &request.panel_types {
+        for optional in &panel_type.optional_items {
+            sources.insert(
+                optional.id.clone(),
+                Source {
+                    item: Item {
+                        id: optional.id.clone(),
+                        width: optional.width,
+                        height: optional.height,
+                        quantity: 1,
+                        can_rotate: optional.can_rotate,
+                        grain: optional.grain,
+                        ..Default::default()
+                    },
+                    index: None,
+                    panel_type_id: Some(panel_type.id.clone()),
+                },
+            );
+        }
+    }
+
+    for (index, item) in request.items.iter().enumerate() {
+        // Banded items are cut smaller than their finished size
+        let (width_reduction, height_reduction) = item
+            .banding
+            .as_ref()
+            .map(|b| (b.width_reduction(), b.height_reduction()))
+            .unwrap_or((0.0, 0.0));
+        let cut = Item {
+            width: item.width - width_reduction,
+            height: item.height - height_reduction,
+            ..item.clone()
+        };
+
+        for id in copy_ids(item) {
+            sources.insert(
+                id,
+                Source {
+                    item: cut.clone(),
+                    index: Some(index),
+                    panel_type_id: None,
+                },
+            );
+        }
+    }
+
+    sources
+}